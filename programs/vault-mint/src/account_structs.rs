@@ -430,6 +430,37 @@ pub struct ClaimRewardsV2<'info> {
     pub system_program: Program<'info, System>,
 }
 
+/// Publishes cohort rewards: a regular V2 epoch (root, cap tracker, pre-funded pool) plus the
+/// `CohortRewards` PDA recording the snapshot cutoff.
+#[derive(Accounts)]
+#[instruction(index: u64)]
+pub struct PublishCohortRewards<'info> {
+    pub rewards_epoch: CreateRewardsEpochV2<'info>,
+
+    #[account(
+        init,
+        payer = rewards_epoch.admin,
+        space = CohortRewards::LEN,
+        seeds = [b"cohort", index.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub cohort: Account<'info, CohortRewards>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Claims a cohort allocation from its V2 epoch pool; the `CohortRewards` PDA must exist.
+#[derive(Accounts)]
+pub struct ClaimCohortRewards<'info> {
+    pub claim: ClaimRewardsV2<'info>,
+
+    #[account(
+        seeds = [b"cohort", claim.epoch.index.to_le_bytes().as_ref()],
+        bump = cohort.bump
+    )]
+    pub cohort: Account<'info, CohortRewards>,
+}
+
 #[derive(Accounts)]
 pub struct RequestRedeem<'info> {
     #[account(mut)]
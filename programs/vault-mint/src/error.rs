@@ -66,4 +66,6 @@ pub enum CustomErrorCode {
     InvalidAllowedExternalMintProgramsLimit = 35,
     #[msg("Claim would exceed the epoch's aggregate reward cap")]
     EpochCapExceeded = 36,
+    #[msg("Cohort cutoff must be a past, non-zero timestamp")]
+    InvalidCohortCutoff = 37,
 }
@@ -59,3 +59,12 @@ pub struct RewardsEpochCreated {
     pub total: u64,
     pub created_ts: i64,
 }
+
+#[event]
+pub struct CohortRewardsPublished {
+    pub admin: Pubkey,
+    pub index: u64,
+    pub merkle_root: [u8; 32],
+    pub total: u64,
+    pub cutoff_ts: i64,
+}
@@ -133,6 +133,28 @@ pub mod vault_mint {
         processor::claim_rewards_v2(ctx, amount, proof)
     }
 
+    /// Publishes cohort (early-bird) rewards: creates a V2 epoch funded with `total` wYLDS whose
+    /// Merkle root covers only holders in the snapshot taken at `cutoff_ts`, and records the
+    /// cutoff in the `CohortRewards` PDA.
+    pub fn publish_cohort_rewards(
+        ctx: Context<PublishCohortRewards>,
+        index: u64,
+        merkle_root: [u8; 32],
+        total: u64,
+        cutoff_ts: i64,
+    ) -> Result<()> {
+        processor::publish_cohort_rewards(ctx, index, merkle_root, total, cutoff_ts)
+    }
+
+    /// Claims a cohort allocation via Merkle proof (same leaf format and cap as V2 epochs).
+    pub fn claim_cohort_rewards(
+        ctx: Context<ClaimCohortRewards>,
+        amount: u64,
+        proof: Vec<ProofNode>,
+    ) -> Result<()> {
+        processor::claim_cohort_rewards(ctx, amount, proof)
+    }
+
     /// Allows an external authorized program to mint tokens to a specified account.
     /// The calling_program account identifies the CPI caller; it must match either
     /// config.allowed_external_mint_program (legacy) or be listed in the
@@ -486,38 +486,55 @@ pub fn create_rewards_epoch_v2(
     merkle_root: [u8; 32],
     total: u64,
 ) -> Result<()> {
-    require!(!ctx.accounts.config.paused, CustomErrorCode::ProtocolPaused);
+    fund_rewards_epoch_v2(
+        ctx.accounts,
+        ctx.bumps.mint_authority,
+        index,
+        merkle_root,
+        total,
+    )
+}
+
+/// Shared by `create_rewards_epoch_v2` and `publish_cohort_rewards`.
+fn fund_rewards_epoch_v2(
+    accounts: &mut CreateRewardsEpochV2,
+    mint_authority_bump: u8,
+    index: u64,
+    merkle_root: [u8; 32],
+    total: u64,
+) -> Result<()> {
+    require!(!accounts.config.paused, CustomErrorCode::ProtocolPaused);
     require!(
-        ctx.accounts
+        accounts
             .config
             .rewards_administrators
-            .contains(&ctx.accounts.admin.key()),
+            .contains(&accounts.admin.key()),
         CustomErrorCode::InvalidRewardsAdministrator
     );
     require!(total > 0, CustomErrorCode::InvalidAmount);
 
-    let e = &mut ctx.accounts.epoch;
+    let e = &mut accounts.epoch;
     e.index = index;
     e.merkle_root = merkle_root;
     e.total = total;
     e.created_ts = Clock::get()?.unix_timestamp;
 
-    let cap = &mut ctx.accounts.epoch_cap;
+    let cap = &mut accounts.epoch_cap;
     cap.index = index;
     cap.total = total;
     cap.claimed_total = 0;
 
     // Pre-fund the pool so `pool.amount == cap.total - cap.claimed_total` holds from creation.
-    let seeds: &[&[u8]] = &[b"mint_authority", &[ctx.bumps.mint_authority]];
+    let seeds: &[&[u8]] = &[b"mint_authority", &[mint_authority_bump]];
     let signer = &[&seeds[..]];
     let cpi_accounts = MintTo {
-        mint: ctx.accounts.mint.to_account_info(),
-        to: ctx.accounts.epoch_rewards_pool.to_account_info(),
-        authority: ctx.accounts.mint_authority.to_account_info(),
+        mint: accounts.mint.to_account_info(),
+        to: accounts.epoch_rewards_pool.to_account_info(),
+        authority: accounts.mint_authority.to_account_info(),
     };
     token::mint_to(
         CpiContext::new_with_signer(
-            ctx.accounts.token_program.to_account_info(),
+            accounts.token_program.to_account_info(),
             cpi_accounts,
             signer,
         ),
@@ -525,7 +542,7 @@ pub fn create_rewards_epoch_v2(
     )?;
 
     emit!(RewardsEpochCreated {
-        admin: ctx.accounts.admin.key(),
+        admin: accounts.admin.key(),
         index,
         merkle_root,
         total,
@@ -538,15 +555,30 @@ pub fn create_rewards_epoch_v2(
 /// Claims rewards from a V2 epoch. Verifies the Merkle proof, enforces the aggregate cap, then
 /// transfers `amount` from `epoch_rewards_pool` to the user (no minting).
 pub fn claim_rewards_v2(ctx: Context<ClaimRewardsV2>, amount: u64, proof: Vec<ProofNode>) -> Result<()> {
-    require!(!ctx.accounts.config.paused, CustomErrorCode::ProtocolPaused);
+    execute_claim_rewards_v2(
+        ctx.accounts,
+        ctx.bumps.epoch_rewards_pool_authority,
+        amount,
+        proof,
+    )
+}
+
+/// Shared by `claim_rewards_v2` and `claim_cohort_rewards`.
+fn execute_claim_rewards_v2(
+    accounts: &mut ClaimRewardsV2,
+    pool_authority_bump: u8,
+    amount: u64,
+    proof: Vec<ProofNode>,
+) -> Result<()> {
+    require!(!accounts.config.paused, CustomErrorCode::ProtocolPaused);
     require!(amount > 0, CustomErrorCode::InvalidAmount);
 
     // Verify the Merkle proof first so invalid claims fail with a clear error before cap state
     // is checked. leaf = sha256(user || amount_le || epoch_index_le)
     let mut data = Vec::with_capacity(32 + 8 + 8);
-    data.extend_from_slice(ctx.accounts.user.key.as_ref());
+    data.extend_from_slice(accounts.user.key.as_ref());
     data.extend_from_slice(&amount.to_le_bytes());
-    data.extend_from_slice(&ctx.accounts.epoch.index.to_le_bytes());
+    data.extend_from_slice(&accounts.epoch.index.to_le_bytes());
     let mut node = hashv(&[&data]).to_bytes();
 
     msg!("User Leaf node: {}", hex::encode(node));
@@ -568,47 +600,46 @@ pub fn claim_rewards_v2(ctx: Context<ClaimRewardsV2>, amount: u64, proof: Vec<Pr
     }
 
     msg!("Computed root: {}", hex::encode(node));
-    msg!("Expected root: {}", hex::encode(ctx.accounts.epoch.merkle_root));
+    msg!("Expected root: {}", hex::encode(accounts.epoch.merkle_root));
 
     require!(
-        node == ctx.accounts.epoch.merkle_root,
+        node == accounts.epoch.merkle_root,
         CustomErrorCode::InvalidMerkleProof
     );
 
     // Enforce the aggregate cap. checked_add guards against overflow on a crafted amount.
     require!(
-        ctx.accounts
+        accounts
             .epoch_cap
             .claimed_total
             .checked_add(amount)
             .ok_or(CustomErrorCode::InvalidAmount)?
-            <= ctx.accounts.epoch_cap.total,
+            <= accounts.epoch_cap.total,
         CustomErrorCode::EpochCapExceeded
     );
 
-    let index_bytes = ctx.accounts.epoch.index.to_le_bytes();
+    let index_bytes = accounts.epoch.index.to_le_bytes();
     let seeds: &[&[u8]] = &[
         b"epoch_rewards_pool_authority",
         index_bytes.as_ref(),
-        &[ctx.bumps.epoch_rewards_pool_authority],
+        &[pool_authority_bump],
     ];
     let signer = &[&seeds[..]];
     let cpi_accounts = Transfer {
-        from: ctx.accounts.epoch_rewards_pool.to_account_info(),
-        to: ctx.accounts.user_mint_token_account.to_account_info(),
-        authority: ctx.accounts.epoch_rewards_pool_authority.to_account_info(),
+        from: accounts.epoch_rewards_pool.to_account_info(),
+        to: accounts.user_mint_token_account.to_account_info(),
+        authority: accounts.epoch_rewards_pool_authority.to_account_info(),
     };
     token::transfer(
         CpiContext::new_with_signer(
-            ctx.accounts.token_program.to_account_info(),
+            accounts.token_program.to_account_info(),
             cpi_accounts,
             signer,
         ),
         amount,
     )?;
 
-    ctx.accounts.epoch_cap.claimed_total = ctx
-        .accounts
+    accounts.epoch_cap.claimed_total = accounts
         .epoch_cap
         .claimed_total
         .checked_add(amount)
@@ -616,17 +647,76 @@ pub fn claim_rewards_v2(ctx: Context<ClaimRewardsV2>, amount: u64, proof: Vec<Pr
 
     msg!("Emitting RewardsClaimed");
     emit!(RewardsClaimed {
-        user: ctx.accounts.user.key(),
-        epoch: ctx.accounts.epoch.index,
+        user: accounts.user.key(),
+        epoch: accounts.epoch.index,
         amount,
-        mint: ctx.accounts.config.mint,
-        vault: ctx.accounts.config.vault,
+        mint: accounts.config.mint,
+        vault: accounts.config.vault,
     });
     msg!("Emitted RewardsClaimed");
 
     Ok(())
 }
 
+/// Publishes early-bird (cohort) rewards: a V2 epoch whose Merkle allocations were computed
+/// off-chain from a holder snapshot taken at `cutoff_ts`, plus a `CohortRewards` PDA recording
+/// that cutoff. Funding, caps and claim records are exactly those of a V2 epoch.
+pub fn publish_cohort_rewards(
+    ctx: Context<PublishCohortRewards>,
+    index: u64,
+    merkle_root: [u8; 32],
+    total: u64,
+    cutoff_ts: i64,
+) -> Result<()> {
+    require!(
+        cutoff_ts > 0 && cutoff_ts <= Clock::get()?.unix_timestamp,
+        CustomErrorCode::InvalidCohortCutoff
+    );
+
+    fund_rewards_epoch_v2(
+        &mut ctx.accounts.rewards_epoch,
+        ctx.bumps.rewards_epoch.mint_authority,
+        index,
+        merkle_root,
+        total,
+    )?;
+
+    let cohort = &mut ctx.accounts.cohort;
+    cohort.index = index;
+    cohort.cutoff_ts = cutoff_ts;
+    cohort.bump = ctx.bumps.cohort;
+
+    emit!(CohortRewardsPublished {
+        admin: ctx.accounts.rewards_epoch.admin.key(),
+        index,
+        merkle_root,
+        total,
+        cutoff_ts,
+    });
+
+    Ok(())
+}
+
+/// Claims a cohort allocation. Proof verification, the aggregate cap and the one-time
+/// `ClaimRecord` are shared with `claim_rewards_v2`; the `CohortRewards` PDA must exist.
+pub fn claim_cohort_rewards(
+    ctx: Context<ClaimCohortRewards>,
+    amount: u64,
+    proof: Vec<ProofNode>,
+) -> Result<()> {
+    msg!(
+        "Cohort {} claim (snapshot cutoff {})",
+        ctx.accounts.cohort.index,
+        ctx.accounts.cohort.cutoff_ts
+    );
+    execute_claim_rewards_v2(
+        &mut ctx.accounts.claim,
+        ctx.bumps.claim.epoch_rewards_pool_authority,
+        amount,
+        proof,
+    )
+}
+
 /// Allows an authorized external program to mint wYLDS tokens into a destination account.
 /// Authorization uses two complementary paths for a safe, zero-downtime migration:
 ///
@@ -52,6 +52,21 @@ impl EpochCapTracker {
     pub const LEN: usize = 8 + 8 + 8 + 8; // discriminator + index + total + claimed_total
 }
 
+/// Marks a V2 epoch as cohort (early-bird) rewards and records the snapshot cutoff the
+/// off-chain allocation was computed from.
+#[account]
+pub struct CohortRewards {
+    /// Mirrors `RewardsEpoch.index` of the epoch that funds this cohort.
+    pub index: u64,
+    /// Unix timestamp of the holder snapshot; only holders before it are in the Merkle tree.
+    pub cutoff_ts: i64,
+    pub bump: u8,
+}
+
+impl CohortRewards {
+    pub const LEN: usize = 8 + 8 + 8 + 1; // discriminator + index + cutoff_ts + bump
+}
+
 #[account]
 pub struct RedemptionRequest {
     pub user: Pubkey,
@@ -1,6 +1,8 @@
 use crate::error::*;
 use crate::state::*;
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program_option::COption;
+use anchor_spl::associated_token::AssociatedToken;
 use anchor_spl::token::{Mint, Token, TokenAccount};
 
 #[allow(deprecated)]
@@ -50,6 +52,8 @@ pub struct Initialize<'info> {
     )]
     pub stake_vault_token_account_config: Account<'info, StakeVaultTokenAccountConfig>,
 
+    /// Must be a classic SPL Token mint. Token-2022 mints (including interest-bearing ones, whose
+    /// accrued interest `amount` would not reflect) fail here with AccountOwnedByWrongProgram.
     pub vault_token_mint: Account<'info, Mint>,
     pub mint: Account<'info, Mint>,
 
@@ -75,6 +79,17 @@ pub struct Pause<'info> {
     )]
     pub stake_config: Account<'info, StakeConfig>,
 
+    /// Records when the protocol was paused (paused_at) and holds paused_operations.
+    #[account(
+        mut,
+        seeds = [
+            b"stake_operations_config",
+            stake_config.key().as_ref(),
+        ],
+        bump = stake_operations_config.bump,
+    )]
+    pub stake_operations_config: Account<'info, StakeOperationsConfig>,
+
     pub signer: Signer<'info>,
 }
 
@@ -160,11 +175,32 @@ pub struct Deposit<'info> {
     )]
     pub stake_price_config: Box<Account<'info, StakePriceConfig>>,
 
+    /// Accumulates rounding dust.
+    #[account(
+        mut,
+        seeds = [
+            b"stake_operations_config",
+            stake_config.key().as_ref(),
+        ],
+        bump = stake_operations_config.bump,
+    )]
+    pub stake_operations_config: Box<Account<'info, StakeOperationsConfig>>,
+
+    /// Insurance fund (StakeOperationsConfig.insurance_token_account). Required when
+    /// insurance_bps > 0; otherwise pass the program's own ID (None).
+    #[account(
+        mut,
+        constraint = insurance_token_account.key() == stake_operations_config.insurance_token_account @ CustomErrorCode::InvalidInsuranceTokenAccount
+    )]
+    pub insurance_token_account: Option<Box<Account<'info, TokenAccount>>>,
+
     pub token_program: Program<'info, Token>,
 }
 
+// Same as Deposit, but creates the signer's stake token (PRIME) ATA if it does not exist yet.
+// Rent for the ATA is paid by the signer.
 #[derive(Accounts)]
-pub struct Redeem<'info> {
+pub struct DepositWithAta<'info> {
     #[account(
         seeds = [b"stake_config"],
         bump = stake_config.bump
@@ -185,11 +221,11 @@ pub struct Redeem<'info> {
         token::mint = stake_config.vault,
         constraint = vault_token_account.mint == stake_config.vault @ CustomErrorCode::InvalidVaultMint,
         constraint = vault_token_account.key() == stake_vault_token_account_config.vault_token_account @ CustomErrorCode::InvalidVaultTokenAccount,
-        constraint = vault_token_account.owner == stake_vault_token_account_config.vault_authority @ CustomErrorCode::InvalidVaultAuthority,
+        constraint = vault_token_account.owner == stake_vault_token_account_config.vault_authority @ CustomErrorCode::InvalidVaultAuthority
     )]
     pub vault_token_account: Box<Account<'info, TokenAccount>>,
 
-    /// CHECK: This is a PDA vault authority, validated by seeds and token account owner constraint
+    /// CHECK: This is a PDA that acts as vault authority, validated by seeds constraint
     #[account(
         seeds = [b"vault_authority"],
         bump,
@@ -197,47 +233,44 @@ pub struct Redeem<'info> {
     )]
     pub vault_authority: UncheckedAccount<'info>,
 
-    #[account(mut)]
-    pub signer: Signer<'info>,
-
-    /// Optional legacy unbonding ticket from the old two-step flow.
-    /// If present (pass the ticket PDA address), it will be closed and rent returned to signer.
-    /// If no ticket exists, pass the program's own ID — Anchor 0.31 treats it as None and skips all constraints.
     #[account(
         mut,
-        close = signer,
-        seeds = [b"ticket", signer.key().as_ref()],
-        bump,
+        constraint = mint.key() == stake_config.mint @ CustomErrorCode::InvalidMint
     )]
-    pub ticket: Option<Account<'info, UnbondingTicket>>,
+    pub mint: Account<'info, Mint>,
 
     #[account(
         mut,
-        token::mint = stake_config.vault,
-        constraint = user_vault_token_account.mint == stake_config.vault @ CustomErrorCode::InvalidVaultMint,
-        constraint = user_vault_token_account.owner == signer.key() @ CustomErrorCode::InvalidTokenOwner
+        constraint = vault_mint.key() == stake_config.vault @ CustomErrorCode::InvalidVaultMint
     )]
-    pub user_vault_token_account: Box<Account<'info, TokenAccount>>,
+    pub vault_mint: Account<'info, Mint>,
 
+    /// CHECK: This is a PDA that acts as mint authority, validated by seeds constraint
     #[account(
-        mut,
-        token::mint = stake_config.mint,
-        constraint = user_mint_token_account.mint == stake_config.mint @ CustomErrorCode::InvalidMint,
-        constraint = user_mint_token_account.owner == signer.key() @ CustomErrorCode::InvalidTokenOwner
+        seeds = [b"mint_authority"],
+        bump,
+        constraint = mint_authority.key() == mint.mint_authority.unwrap() @ CustomErrorCode::InvalidMintAuthority
     )]
-    pub user_mint_token_account: Box<Account<'info, TokenAccount>>,
+    pub mint_authority: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
 
     #[account(
         mut,
-        constraint = mint.key() == stake_config.mint @ CustomErrorCode::InvalidMint
+        token::mint = stake_config.vault,
+        constraint = user_vault_token_account.mint == stake_config.vault @ CustomErrorCode::InvalidVaultMint,
+        constraint = user_vault_token_account.owner == signer.key() @ CustomErrorCode::InvalidTokenOwner
     )]
-    pub mint: Account<'info, Mint>,
+    pub user_vault_token_account: Box<Account<'info, TokenAccount>>,
 
     #[account(
-        mut,
-        constraint = vault_mint.key() == stake_config.vault @ CustomErrorCode::InvalidVaultMint
+        init_if_needed,
+        payer = signer,
+        associated_token::mint = mint,
+        associated_token::authority = signer,
     )]
-    pub vault_mint: Account<'info, Mint>,
+    pub user_mint_token_account: Box<Account<'info, TokenAccount>>,
 
     #[account(
         seeds = [
@@ -248,52 +281,34 @@ pub struct Redeem<'info> {
     )]
     pub stake_price_config: Box<Account<'info, StakePriceConfig>>,
 
-    pub token_program: Program<'info, Token>,
-}
-
-// Helper function to derive the program data address
-fn get_program_data_address(program_id: &Pubkey) -> Pubkey {
-    Pubkey::find_program_address(&[program_id.as_ref()], &bpf_loader_upgradeable::id()).0
-}
-
-#[derive(Accounts)]
-pub struct UpdateFreezeAdministrators<'info> {
+    /// Accumulates rounding dust.
     #[account(
         mut,
-        seeds = [b"stake_config"],
-        bump = stake_config.bump
-    )]
-    pub stake_config: Account<'info, StakeConfig>,
-
-    /// CHECK: This is the program data account that contains the update authority
-    #[account(
-        constraint = program_data.key() == get_program_data_address(&crate::id()) @ CustomErrorCode::InvalidProgramData
+        seeds = [
+            b"stake_operations_config",
+            stake_config.key().as_ref(),
+        ],
+        bump = stake_operations_config.bump,
     )]
-    pub program_data: UncheckedAccount<'info>,
-
-    pub signer: Signer<'info>,
-}
+    pub stake_operations_config: Box<Account<'info, StakeOperationsConfig>>,
 
-#[derive(Accounts)]
-pub struct UpdateRewardsAdministrators<'info> {
+    /// Insurance fund (StakeOperationsConfig.insurance_token_account). Required when
+    /// insurance_bps > 0; otherwise pass the program's own ID (None).
     #[account(
         mut,
-        seeds = [b"stake_config"],
-        bump = stake_config.bump
-    )]
-    pub stake_config: Account<'info, StakeConfig>,
-
-    /// CHECK: This is the program data account that contains the update authority
-    #[account(
-        constraint = program_data.key() == get_program_data_address(&crate::id()) @ CustomErrorCode::InvalidProgramData
+        constraint = insurance_token_account.key() == stake_operations_config.insurance_token_account @ CustomErrorCode::InvalidInsuranceTokenAccount
     )]
-    pub program_data: UncheckedAccount<'info>,
+    pub insurance_token_account: Option<Box<Account<'info, TokenAccount>>>,
 
-    pub signer: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
 }
 
+// Same as Deposit, but a relayer signs and pays the fees: the user's vault tokens move under an
+// SPL approve the user granted the relayer, and the shares are minted to the user.
 #[derive(Accounts)]
-pub struct FreezeTokenAccount<'info> {
+pub struct DepositWithRelayer<'info> {
     #[account(
         seeds = [b"stake_config"],
         bump = stake_config.bump
@@ -301,128 +316,115 @@ pub struct FreezeTokenAccount<'info> {
     pub stake_config: Account<'info, StakeConfig>,
 
     #[account(
-        mut,
-        constraint = token_account.mint == mint.key() @ CustomErrorCode::InvalidMint
+        seeds = [
+            b"stake_vault_token_account_config",
+            stake_config.key().as_ref(),
+        ],
+        bump = stake_vault_token_account_config.bump,
     )]
-    pub token_account: Account<'info, TokenAccount>,
+    pub stake_vault_token_account_config: Account<'info, StakeVaultTokenAccountConfig>,
 
     #[account(
-        constraint = mint.freeze_authority == Some(freeze_authority_pda.key()).into() @ CustomErrorCode::InvalidFreezeAuthority,
-        constraint = stake_config.mint == mint.key() @ CustomErrorCode::InvalidMint
+        mut,
+        token::mint = stake_config.vault,
+        constraint = vault_token_account.mint == stake_config.vault @ CustomErrorCode::InvalidVaultMint,
+        constraint = vault_token_account.key() == stake_vault_token_account_config.vault_token_account @ CustomErrorCode::InvalidVaultTokenAccount,
+        constraint = vault_token_account.owner == stake_vault_token_account_config.vault_authority @ CustomErrorCode::InvalidVaultAuthority
     )]
-    pub mint: Account<'info, Mint>,
+    pub vault_token_account: Box<Account<'info, TokenAccount>>,
 
-    /// CHECK: This is the freeze authority PDA
+    /// CHECK: This is a PDA that acts as vault authority, validated by seeds constraint
     #[account(
-        seeds = [b"freeze_authority"],
-        bump
+        seeds = [b"vault_authority"],
+        bump,
+        constraint = vault_authority.key() == stake_vault_token_account_config.vault_authority @ CustomErrorCode::InvalidVaultAuthority
     )]
-    pub freeze_authority_pda: UncheckedAccount<'info>,
-
-    pub signer: Signer<'info>,
-    pub token_program: Program<'info, Token>,
-}
+    pub vault_authority: UncheckedAccount<'info>,
 
-#[derive(Accounts)]
-pub struct ThawTokenAccount<'info> {
     #[account(
-        seeds = [b"stake_config"],
-        bump = stake_config.bump
+        mut,
+        constraint = mint.key() == stake_config.mint @ CustomErrorCode::InvalidMint
     )]
-    pub stake_config: Account<'info, StakeConfig>,
+    pub mint: Account<'info, Mint>,
 
     #[account(
         mut,
-        constraint = token_account.mint == mint.key() @ CustomErrorCode::InvalidMint
+        constraint = vault_mint.key() == stake_config.vault @ CustomErrorCode::InvalidVaultMint
     )]
-    pub token_account: Account<'info, TokenAccount>,
+    pub vault_mint: Account<'info, Mint>,
 
+    /// CHECK: This is a PDA that acts as mint authority, validated by seeds constraint
     #[account(
-        constraint = mint.freeze_authority == Some(freeze_authority_pda.key()).into() @ CustomErrorCode::InvalidFreezeAuthority,
-        constraint = stake_config.mint == mint.key() @ CustomErrorCode::InvalidMint
+        seeds = [b"mint_authority"],
+        bump,
+        constraint = mint_authority.key() == mint.mint_authority.unwrap() @ CustomErrorCode::InvalidMintAuthority
     )]
-    pub mint: Account<'info, Mint>,
+    pub mint_authority: UncheckedAccount<'info>,
 
-    /// CHECK: This is the freeze authority PDA
-    #[account(
-        seeds = [b"freeze_authority"],
-        bump
-    )]
-    pub freeze_authority_pda: UncheckedAccount<'info>,
+    /// Pays the fees and moves the user's vault tokens as their approved delegate.
+    #[account(mut)]
+    pub relayer: Signer<'info>,
 
-    pub signer: Signer<'info>,
-    pub token_program: Program<'info, Token>,
-}
+    /// CHECK: only the beneficiary's key is used; the token accounts below must belong to it.
+    pub user: UncheckedAccount<'info>,
 
-// admin publishes rewards
-#[derive(Accounts)]
-#[instruction(id: u32, amount: u64)]
-pub struct PublishRewards<'info> {
     #[account(
-        seeds = [b"stake_config"], 
-        bump = stake_config.bump
+        mut,
+        token::mint = stake_config.vault,
+        constraint = user_vault_token_account.mint == stake_config.vault @ CustomErrorCode::InvalidVaultMint,
+        constraint = user_vault_token_account.owner == user.key() @ CustomErrorCode::InvalidTokenOwner,
+        constraint = user_vault_token_account.delegate == COption::Some(relayer.key()) @ CustomErrorCode::InvalidRelayerDelegate
     )]
-    pub stake_config: Box<Account<'info, StakeConfig>>,
+    pub user_vault_token_account: Box<Account<'info, TokenAccount>>,
 
     #[account(
-        seeds = [b"config"], 
-        bump = mint_config.bump,
-        seeds::program = mint_program.key()
+        mut,
+        token::mint = stake_config.mint,
+        constraint = user_mint_token_account.mint == stake_config.mint @ CustomErrorCode::InvalidMint,
+        constraint = user_mint_token_account.owner == user.key() @ CustomErrorCode::InvalidTokenOwner
     )]
-    pub mint_config: Box<Account<'info, vault_mint::state::Config>>,
+    pub user_mint_token_account: Box<Account<'info, TokenAccount>>,
 
-    /// PDA that proves this call is from this staking program.
-    /// Signed during the CPI to vault-mint. Only this program can produce a valid
-    /// signer for this address because it is derived from crate::id().
-    /// CHECK: This is a PDA derived from this program's id, validated by seeds
     #[account(
-        seeds = [b"external_mint_authority"],
-        bump
+        seeds = [
+            b"stake_price_config",
+            stake_config.key().as_ref(),
+        ],
+        bump = stake_price_config.bump,
     )]
-    pub external_mint_authority: UncheckedAccount<'info>,
-
-    /// CHECK: hastra vault-mint program's executable
-    pub mint_program: AccountInfo<'info>,
-
-    /// This program's own account, passed to vault-mint as the calling_program identifier
-    /// so vault-mint can verify the caller against its allowed-program list.
-    /// CHECK: Address is constrained to this program's deployed id
-    #[account(constraint = this_program.key() == crate::id() @ CustomErrorCode::InvalidAuthority)]
-    pub this_program: AccountInfo<'info>,
+    pub stake_price_config: Box<Account<'info, StakePriceConfig>>,
 
-    /// The AllowedExternalMintPrograms PDA from vault-mint, passed through during the CPI to
-    /// vault-mint::external_program_mint. vault-mint uses it to authorize callers beyond the
-    /// legacy single-program field. This account is required here and in vault-mint, so it
-    /// must already exist on-chain (typically created during vault-mint upgrade / migration
-    /// via register_allowed_external_mint_program, which uses init_if_needed) before
-    /// publish_rewards can succeed. Until any programs are registered, account data may be
-    /// empty or too short to deserialize; vault-mint then treats the extended list as empty
-    /// and only the legacy config field applies.
-    /// CHECK: Derived from vault-mint's config; list membership is enforced in vault-mint
+    /// Accumulates rounding dust.
     #[account(
-        seeds = [b"allowed_external_mint_programs", mint_config.key().as_ref()],
-        seeds::program = mint_program.key(),
-        bump,
+        mut,
+        seeds = [
+            b"stake_operations_config",
+            stake_config.key().as_ref(),
+        ],
+        bump = stake_operations_config.bump,
     )]
-    pub vault_mint_allowed_external_programs: UncheckedAccount<'info>,
-
-    #[account(mut)]
-    pub admin: Signer<'info>,
+    pub stake_operations_config: Box<Account<'info, StakeOperationsConfig>>,
 
+    /// Insurance fund (StakeOperationsConfig.insurance_token_account). Required when
+    /// insurance_bps > 0; otherwise pass the program's own ID (None).
     #[account(
         mut,
-        constraint = rewards_mint.key() == stake_config.vault @ CustomErrorCode::InvalidMint,
-        constraint = rewards_mint.mint_authority.unwrap() == rewards_mint_authority.key() @ CustomErrorCode::InvalidMintAuthority
+        constraint = insurance_token_account.key() == stake_operations_config.insurance_token_account @ CustomErrorCode::InvalidInsuranceTokenAccount
     )]
-    pub rewards_mint: Box<Account<'info, Mint>>, // this seems odd, but the rewards are in the vault token mint
+    pub insurance_token_account: Option<Box<Account<'info, TokenAccount>>>,
 
-    /// CHECK: This is a PDA that acts as mint authority, validated by seeds constraint
+    pub token_program: Program<'info, Token>,
+}
+
+// Same as Deposit, but first CPIs into the allowlisted swap program (with the remaining accounts
+// and the caller's route data) and deposits the vault tokens the swap delivered.
+#[derive(Accounts)]
+pub struct ZapDeposit<'info> {
     #[account(
-        seeds = [b"mint_authority"],
-        seeds::program = mint_program.key(),
-        bump,
+        seeds = [b"stake_config"],
+        bump = stake_config.bump
     )]
-    pub rewards_mint_authority: UncheckedAccount<'info>,
+    pub stake_config: Account<'info, StakeConfig>,
 
     #[account(
         seeds = [
@@ -431,7 +433,7 @@ pub struct PublishRewards<'info> {
         ],
         bump = stake_vault_token_account_config.bump,
     )]
-    pub stake_vault_token_account_config: Box<Account<'info, StakeVaultTokenAccountConfig>>,
+    pub stake_vault_token_account_config: Account<'info, StakeVaultTokenAccountConfig>,
 
     #[account(
         mut,
@@ -454,21 +456,944 @@ pub struct PublishRewards<'info> {
         mut,
         constraint = mint.key() == stake_config.mint @ CustomErrorCode::InvalidMint
     )]
-    pub mint: Box<Account<'info, Mint>>,
+    pub mint: Account<'info, Mint>,
 
-    /// Reward record PDA to prevent duplicates
     #[account(
-        init,
-        payer = admin,
-        space = RewardPublicationRecord::LEN,
-        seeds = [
-            b"reward_record",
-            id.to_le_bytes().as_ref(),
-            amount.to_le_bytes().as_ref(),
-        ],
-        bump
+        mut,
+        constraint = vault_mint.key() == stake_config.vault @ CustomErrorCode::InvalidVaultMint
     )]
-    pub reward_record: Box<Account<'info, RewardPublicationRecord>>,
+    pub vault_mint: Account<'info, Mint>,
+
+    /// CHECK: This is a PDA that acts as mint authority, validated by seeds constraint
+    #[account(
+        seeds = [b"mint_authority"],
+        bump,
+        constraint = mint_authority.key() == mint.mint_authority.unwrap() @ CustomErrorCode::InvalidMintAuthority
+    )]
+    pub mint_authority: UncheckedAccount<'info>,
+
+    #[account()]
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        token::mint = stake_config.vault,
+        constraint = user_vault_token_account.mint == stake_config.vault @ CustomErrorCode::InvalidVaultMint,
+        constraint = user_vault_token_account.owner == signer.key() @ CustomErrorCode::InvalidTokenOwner
+    )]
+    pub user_vault_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = stake_config.mint,
+        constraint = user_mint_token_account.mint == stake_config.mint @ CustomErrorCode::InvalidMint,
+        constraint = user_mint_token_account.owner == signer.key() @ CustomErrorCode::InvalidTokenOwner
+    )]
+    pub user_mint_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        seeds = [
+            b"stake_price_config",
+            stake_config.key().as_ref(),
+        ],
+        bump = stake_price_config.bump,
+    )]
+    pub stake_price_config: Box<Account<'info, StakePriceConfig>>,
+
+    /// Accumulates rounding dust.
+    #[account(
+        mut,
+        seeds = [
+            b"stake_operations_config",
+            stake_config.key().as_ref(),
+        ],
+        bump = stake_operations_config.bump,
+    )]
+    pub stake_operations_config: Box<Account<'info, StakeOperationsConfig>>,
+
+    /// Insurance fund (StakeOperationsConfig.insurance_token_account). Required when
+    /// insurance_bps > 0; otherwise pass the program's own ID (None).
+    #[account(
+        mut,
+        constraint = insurance_token_account.key() == stake_operations_config.insurance_token_account @ CustomErrorCode::InvalidInsuranceTokenAccount
+    )]
+    pub insurance_token_account: Option<Box<Account<'info, TokenAccount>>>,
+
+    /// The input token the swap spends; only its balance is read, to bound the spend by amount_in.
+    #[account(
+        constraint = user_input_token_account.owner == signer.key() @ CustomErrorCode::InvalidTokenOwner
+    )]
+    pub user_input_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: must be StakeOperationsConfig.swap_program; Pubkey::default() there disables zaps.
+    #[account(
+        executable,
+        constraint = stake_operations_config.swap_program != Pubkey::default() @ CustomErrorCode::SwapProgramNotAllowed,
+        constraint = swap_program.key() == stake_operations_config.swap_program @ CustomErrorCode::SwapProgramNotAllowed
+    )]
+    pub swap_program: AccountInfo<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+// Same as Deposit, but mints the shares into the signer's vesting escrow instead of their stake
+// token account. Rent for the vesting account and escrow is paid by the signer.
+#[derive(Accounts)]
+pub struct DepositVesting<'info> {
+    #[account(
+        seeds = [b"stake_config"],
+        bump = stake_config.bump
+    )]
+    pub stake_config: Account<'info, StakeConfig>,
+
+    #[account(
+        seeds = [
+            b"stake_vault_token_account_config",
+            stake_config.key().as_ref(),
+        ],
+        bump = stake_vault_token_account_config.bump,
+    )]
+    pub stake_vault_token_account_config: Account<'info, StakeVaultTokenAccountConfig>,
+
+    #[account(
+        mut,
+        token::mint = stake_config.vault,
+        constraint = vault_token_account.mint == stake_config.vault @ CustomErrorCode::InvalidVaultMint,
+        constraint = vault_token_account.key() == stake_vault_token_account_config.vault_token_account @ CustomErrorCode::InvalidVaultTokenAccount,
+        constraint = vault_token_account.owner == stake_vault_token_account_config.vault_authority @ CustomErrorCode::InvalidVaultAuthority
+    )]
+    pub vault_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: This is a PDA that acts as vault authority, validated by seeds constraint
+    #[account(
+        seeds = [b"vault_authority"],
+        bump,
+        constraint = vault_authority.key() == stake_vault_token_account_config.vault_authority @ CustomErrorCode::InvalidVaultAuthority
+    )]
+    pub vault_authority: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        constraint = mint.key() == stake_config.mint @ CustomErrorCode::InvalidMint
+    )]
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        constraint = vault_mint.key() == stake_config.vault @ CustomErrorCode::InvalidVaultMint
+    )]
+    pub vault_mint: Account<'info, Mint>,
+
+    /// CHECK: This is a PDA that acts as mint authority, validated by seeds constraint
+    #[account(
+        seeds = [b"mint_authority"],
+        bump,
+        constraint = mint_authority.key() == mint.mint_authority.unwrap() @ CustomErrorCode::InvalidMintAuthority
+    )]
+    pub mint_authority: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        token::mint = stake_config.vault,
+        constraint = user_vault_token_account.mint == stake_config.vault @ CustomErrorCode::InvalidVaultMint,
+        constraint = user_vault_token_account.owner == signer.key() @ CustomErrorCode::InvalidTokenOwner
+    )]
+    pub user_vault_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// The signer's vesting schedule, created on their first vesting deposit.
+    #[account(
+        init_if_needed,
+        payer = signer,
+        space = VestingAccount::LEN,
+        seeds = [
+            b"vesting",
+            stake_config.key().as_ref(),
+            signer.key().as_ref(),
+        ],
+        bump
+    )]
+    pub vesting_account: Box<Account<'info, VestingAccount>>,
+
+    /// Stake token (PRIME) escrow owned by vesting_account; receives the minted shares.
+    #[account(
+        init_if_needed,
+        payer = signer,
+        seeds = [b"vesting_escrow", vesting_account.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = vesting_account,
+    )]
+    pub vesting_escrow: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        seeds = [
+            b"stake_price_config",
+            stake_config.key().as_ref(),
+        ],
+        bump = stake_price_config.bump,
+    )]
+    pub stake_price_config: Box<Account<'info, StakePriceConfig>>,
+
+    /// Accumulates rounding dust.
+    #[account(
+        mut,
+        seeds = [
+            b"stake_operations_config",
+            stake_config.key().as_ref(),
+        ],
+        bump = stake_operations_config.bump,
+    )]
+    pub stake_operations_config: Box<Account<'info, StakeOperationsConfig>>,
+
+    /// Insurance fund (StakeOperationsConfig.insurance_token_account). Required when
+    /// insurance_bps > 0; otherwise pass the program's own ID (None).
+    #[account(
+        mut,
+        constraint = insurance_token_account.key() == stake_operations_config.insurance_token_account @ CustomErrorCode::InvalidInsuranceTokenAccount
+    )]
+    pub insurance_token_account: Option<Box<Account<'info, TokenAccount>>>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+// Releases the currently vested shares of the signer's vesting schedule.
+#[derive(Accounts)]
+pub struct ClaimVested<'info> {
+    #[account(
+        seeds = [b"stake_config"],
+        bump = stake_config.bump
+    )]
+    pub stake_config: Account<'info, StakeConfig>,
+
+    /// Holds events_digest, updated by every emitted event.
+    #[account(
+        mut,
+        seeds = [b"stake_operations_config", stake_config.key().as_ref()],
+        bump = stake_operations_config.bump,
+    )]
+    pub stake_operations_config: Account<'info, StakeOperationsConfig>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"vesting",
+            stake_config.key().as_ref(),
+            signer.key().as_ref(),
+        ],
+        bump = vesting_account.bump
+    )]
+    pub vesting_account: Account<'info, VestingAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"vesting_escrow", vesting_account.key().as_ref()],
+        bump,
+    )]
+    pub vesting_escrow: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = user_mint_token_account.mint == stake_config.mint @ CustomErrorCode::InvalidRecipientMint,
+        constraint = user_mint_token_account.owner == signer.key() @ CustomErrorCode::InvalidRecipientOwner
+    )]
+    pub user_mint_token_account: Account<'info, TokenAccount>,
+
+    pub signer: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+// Records (or replaces) the caller's commit-reveal deposit intent.
+#[derive(Accounts)]
+pub struct CommitDeposit<'info> {
+    #[account(
+        seeds = [b"stake_config"],
+        bump = stake_config.bump
+    )]
+    pub stake_config: Account<'info, StakeConfig>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"stake_operations_config",
+            stake_config.key().as_ref(),
+        ],
+        bump = stake_operations_config.bump,
+    )]
+    pub stake_operations_config: Box<Account<'info, StakeOperationsConfig>>,
+
+    #[account(
+        init_if_needed,
+        payer = signer,
+        space = DepositCommitment::LEN,
+        seeds = [
+            b"deposit_commitment",
+            stake_config.key().as_ref(),
+            signer.key().as_ref(),
+        ],
+        bump
+    )]
+    pub deposit_commitment: Account<'info, DepositCommitment>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+// Executes a committed deposit: the regular Deposit accounts plus the commitment being revealed.
+#[derive(Accounts)]
+pub struct RevealDeposit<'info> {
+    pub deposit: Deposit<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"deposit_commitment",
+            deposit.stake_config.key().as_ref(),
+            deposit.signer.key().as_ref(),
+        ],
+        bump = deposit_commitment.bump,
+        close = depositor
+    )]
+    pub deposit_commitment: Account<'info, DepositCommitment>,
+
+    /// Receives the commitment rent; must be the depositor.
+    #[account(
+        mut,
+        address = deposit.signer.key() @ CustomErrorCode::InvalidTokenOwner
+    )]
+    pub depositor: SystemAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct Redeem<'info> {
+    /// Mutable so the circuit breaker can pause the protocol.
+    #[account(
+        mut,
+        seeds = [b"stake_config"],
+        bump = stake_config.bump
+    )]
+    pub stake_config: Account<'info, StakeConfig>,
+
+    #[account(
+        seeds = [
+            b"stake_vault_token_account_config",
+            stake_config.key().as_ref(),
+        ],
+        bump = stake_vault_token_account_config.bump,
+    )]
+    pub stake_vault_token_account_config: Account<'info, StakeVaultTokenAccountConfig>,
+
+    #[account(
+        mut,
+        token::mint = stake_config.vault,
+        constraint = vault_token_account.mint == stake_config.vault @ CustomErrorCode::InvalidVaultMint,
+        constraint = vault_token_account.key() == stake_vault_token_account_config.vault_token_account @ CustomErrorCode::InvalidVaultTokenAccount,
+        constraint = vault_token_account.owner == stake_vault_token_account_config.vault_authority @ CustomErrorCode::InvalidVaultAuthority,
+    )]
+    pub vault_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: This is a PDA vault authority, validated by seeds and token account owner constraint
+    #[account(
+        seeds = [b"vault_authority"],
+        bump,
+        constraint = vault_authority.key() == stake_vault_token_account_config.vault_authority @ CustomErrorCode::InvalidVaultAuthority
+    )]
+    pub vault_authority: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    /// Optional legacy unbonding ticket from the old two-step flow.
+    /// If present (pass the ticket PDA address), it will be closed and rent returned to signer.
+    /// If no ticket exists, pass the program's own ID — Anchor 0.31 treats it as None and skips all constraints.
+    #[account(
+        mut,
+        close = signer,
+        seeds = [b"ticket", signer.key().as_ref()],
+        bump,
+    )]
+    pub ticket: Option<Account<'info, UnbondingTicket>>,
+
+    #[account(
+        mut,
+        token::mint = stake_config.vault,
+        constraint = user_vault_token_account.mint == stake_config.vault @ CustomErrorCode::InvalidRecipientMint,
+        constraint = user_vault_token_account.owner == signer.key() @ CustomErrorCode::InvalidRecipientOwner
+    )]
+    pub user_vault_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = stake_config.mint,
+        constraint = user_mint_token_account.mint == stake_config.mint @ CustomErrorCode::InvalidMint,
+        constraint = user_mint_token_account.owner == signer.key() @ CustomErrorCode::InvalidTokenOwner
+    )]
+    pub user_mint_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = mint.key() == stake_config.mint @ CustomErrorCode::InvalidMint
+    )]
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        constraint = vault_mint.key() == stake_config.vault @ CustomErrorCode::InvalidVaultMint
+    )]
+    pub vault_mint: Account<'info, Mint>,
+
+    #[account(
+        seeds = [
+            b"stake_price_config",
+            stake_config.key().as_ref(),
+        ],
+        bump = stake_price_config.bump,
+    )]
+    pub stake_price_config: Box<Account<'info, StakePriceConfig>>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"stake_operations_config",
+            stake_config.key().as_ref(),
+        ],
+        bump = stake_operations_config.bump,
+    )]
+    pub stake_operations_config: Box<Account<'info, StakeOperationsConfig>>,
+
+    /// Per-user redeem cooldown state, created on the user's first redeem that passes it.
+    /// Required only while redeem_cooldown_seconds > 0; otherwise pass the program's own ID to
+    /// skip it (and the rent for creating it).
+    #[account(
+        init_if_needed,
+        payer = signer,
+        space = UserRedeemState::LEN,
+        seeds = [
+            b"redeem_state",
+            stake_config.key().as_ref(),
+            signer.key().as_ref(),
+        ],
+        bump
+    )]
+    pub redeem_state: Option<Box<Account<'info, UserRedeemState>>>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Read-only program data account for the get_upgrade_authority view.
+#[derive(Accounts)]
+pub struct GetUpgradeAuthority<'info> {
+    /// CHECK: This is the program data account that contains the update authority
+    #[account(
+        constraint = program_data.key() == get_program_data_address(&crate::id()) @ CustomErrorCode::InvalidProgramData
+    )]
+    pub program_data: UncheckedAccount<'info>,
+}
+
+// Helper function to derive the program data address
+fn get_program_data_address(program_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[program_id.as_ref()], &bpf_loader_upgradeable::id()).0
+}
+
+#[derive(Accounts)]
+pub struct UpdateFreezeAdministrators<'info> {
+    #[account(
+        mut,
+        seeds = [b"stake_config"],
+        bump = stake_config.bump
+    )]
+    pub stake_config: Account<'info, StakeConfig>,
+
+    /// Holds admin_list_version.
+    #[account(
+        mut,
+        seeds = [b"stake_operations_config", stake_config.key().as_ref()],
+        bump = stake_operations_config.bump,
+    )]
+    pub stake_operations_config: Account<'info, StakeOperationsConfig>,
+
+    /// CHECK: This is the program data account that contains the update authority
+    #[account(
+        constraint = program_data.key() == get_program_data_address(&crate::id()) @ CustomErrorCode::InvalidProgramData
+    )]
+    pub program_data: UncheckedAccount<'info>,
+
+    pub signer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateRewardsAdministrators<'info> {
+    #[account(
+        mut,
+        seeds = [b"stake_config"],
+        bump = stake_config.bump
+    )]
+    pub stake_config: Account<'info, StakeConfig>,
+
+    /// Holds admin_list_version.
+    #[account(
+        mut,
+        seeds = [b"stake_operations_config", stake_config.key().as_ref()],
+        bump = stake_operations_config.bump,
+    )]
+    pub stake_operations_config: Account<'info, StakeOperationsConfig>,
+
+    /// CHECK: This is the program data account that contains the update authority
+    #[account(
+        constraint = program_data.key() == get_program_data_address(&crate::id()) @ CustomErrorCode::InvalidProgramData
+    )]
+    pub program_data: UncheckedAccount<'info>,
+
+    pub signer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct FreezeTokenAccount<'info> {
+    #[account(
+        seeds = [b"stake_config"],
+        bump = stake_config.bump
+    )]
+    pub stake_config: Account<'info, StakeConfig>,
+
+    /// Holds paused_operations (PAUSE_FREEZE).
+    #[account(
+        seeds = [b"stake_operations_config", stake_config.key().as_ref()],
+        bump = stake_operations_config.bump,
+    )]
+    pub stake_operations_config: Account<'info, StakeOperationsConfig>,
+
+    #[account(
+        mut,
+        constraint = token_account.mint == mint.key() @ CustomErrorCode::InvalidMint
+    )]
+    pub token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        constraint = mint.freeze_authority == Some(freeze_authority_pda.key()).into() @ CustomErrorCode::InvalidFreezeAuthority,
+        constraint = stake_config.mint == mint.key() @ CustomErrorCode::InvalidMint
+    )]
+    pub mint: Account<'info, Mint>,
+
+    /// CHECK: This is the freeze authority PDA
+    #[account(
+        seeds = [b"freeze_authority"],
+        bump
+    )]
+    pub freeze_authority_pda: UncheckedAccount<'info>,
+
+    pub signer: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct ThawTokenAccount<'info> {
+    #[account(
+        seeds = [b"stake_config"],
+        bump = stake_config.bump
+    )]
+    pub stake_config: Account<'info, StakeConfig>,
+
+    #[account(
+        mut,
+        constraint = token_account.mint == mint.key() @ CustomErrorCode::InvalidMint
+    )]
+    pub token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        constraint = mint.freeze_authority == Some(freeze_authority_pda.key()).into() @ CustomErrorCode::InvalidFreezeAuthority,
+        constraint = stake_config.mint == mint.key() @ CustomErrorCode::InvalidMint
+    )]
+    pub mint: Account<'info, Mint>,
+
+    /// CHECK: This is the freeze authority PDA
+    #[account(
+        seeds = [b"freeze_authority"],
+        bump
+    )]
+    pub freeze_authority_pda: UncheckedAccount<'info>,
+
+    pub signer: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+/// Freezes a stake token account and records why in its RestrictionRecord PDA.
+#[derive(Accounts)]
+pub struct RestrictAccount<'info> {
+    #[account(
+        seeds = [b"stake_config"],
+        bump = stake_config.bump
+    )]
+    pub stake_config: Account<'info, StakeConfig>,
+
+    /// Holds paused_operations (PAUSE_FREEZE) and events_digest.
+    #[account(
+        mut,
+        seeds = [b"stake_operations_config", stake_config.key().as_ref()],
+        bump = stake_operations_config.bump,
+    )]
+    pub stake_operations_config: Account<'info, StakeOperationsConfig>,
+
+    #[account(
+        mut,
+        constraint = token_account.mint == mint.key() @ CustomErrorCode::InvalidMint
+    )]
+    pub token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        constraint = mint.freeze_authority == Some(freeze_authority_pda.key()).into() @ CustomErrorCode::InvalidFreezeAuthority,
+        constraint = stake_config.mint == mint.key() @ CustomErrorCode::InvalidMint
+    )]
+    pub mint: Account<'info, Mint>,
+
+    /// CHECK: This is the freeze authority PDA
+    #[account(
+        seeds = [b"freeze_authority"],
+        bump
+    )]
+    pub freeze_authority_pda: UncheckedAccount<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = signer,
+        space = RestrictionRecord::LEN,
+        seeds = [b"restriction", token_account.key().as_ref()],
+        bump
+    )]
+    pub restriction_record: Account<'info, RestrictionRecord>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Thaws a restricted stake token account and records the lift in its RestrictionRecord PDA.
+#[derive(Accounts)]
+pub struct LiftRestriction<'info> {
+    #[account(
+        seeds = [b"stake_config"],
+        bump = stake_config.bump
+    )]
+    pub stake_config: Account<'info, StakeConfig>,
+
+    /// Holds events_digest, updated by every emitted event.
+    #[account(
+        mut,
+        seeds = [b"stake_operations_config", stake_config.key().as_ref()],
+        bump = stake_operations_config.bump,
+    )]
+    pub stake_operations_config: Account<'info, StakeOperationsConfig>,
+
+    #[account(
+        mut,
+        constraint = token_account.mint == mint.key() @ CustomErrorCode::InvalidMint
+    )]
+    pub token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        constraint = mint.freeze_authority == Some(freeze_authority_pda.key()).into() @ CustomErrorCode::InvalidFreezeAuthority,
+        constraint = stake_config.mint == mint.key() @ CustomErrorCode::InvalidMint
+    )]
+    pub mint: Account<'info, Mint>,
+
+    /// CHECK: This is the freeze authority PDA
+    #[account(
+        seeds = [b"freeze_authority"],
+        bump
+    )]
+    pub freeze_authority_pda: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"restriction", token_account.key().as_ref()],
+        bump = restriction_record.bump
+    )]
+    pub restriction_record: Account<'info, RestrictionRecord>,
+
+    pub signer: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+/// Moves the stake mint's freeze authority from the signer to the freeze_authority PDA.
+/// Only callable by the program upgrade authority.
+#[derive(Accounts)]
+pub struct ClaimFreezeAuthority<'info> {
+    #[account(
+        seeds = [b"stake_config"],
+        bump = stake_config.bump
+    )]
+    pub stake_config: Account<'info, StakeConfig>,
+
+    #[account(
+        mut,
+        constraint = stake_config.mint == mint.key() @ CustomErrorCode::InvalidMint
+    )]
+    pub mint: Account<'info, Mint>,
+
+    /// CHECK: This is the freeze authority PDA
+    #[account(
+        seeds = [b"freeze_authority"],
+        bump
+    )]
+    pub freeze_authority_pda: UncheckedAccount<'info>,
+
+    pub signer: Signer<'info>,
+
+    /// CHECK: This is the program data account that contains the update authority
+    #[account(
+        constraint = program_data.key() == get_program_data_address(&crate::id()) @ CustomErrorCode::InvalidProgramData
+    )]
+    pub program_data: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct FreezeAuthorityView<'info> {
+    #[account(
+        seeds = [b"stake_config"],
+        bump = stake_config.bump
+    )]
+    pub stake_config: Account<'info, StakeConfig>,
+
+    #[account(
+        constraint = stake_config.mint == mint.key() @ CustomErrorCode::InvalidMint
+    )]
+    pub mint: Account<'info, Mint>,
+
+    /// CHECK: This is the freeze authority PDA
+    #[account(
+        seeds = [b"freeze_authority"],
+        bump
+    )]
+    pub freeze_authority_pda: UncheckedAccount<'info>,
+}
+
+/// Read-only access to any owner's legacy unbonding ticket (support tooling).
+#[derive(Accounts)]
+#[instruction(owner: Pubkey)]
+pub struct TicketView<'info> {
+    #[account(
+        seeds = [b"ticket", owner.as_ref()],
+        bump
+    )]
+    pub ticket: Account<'info, UnbondingTicket>,
+}
+
+/// Owner closes their legacy unbonding ticket without redeeming; rent goes back to the signer.
+#[derive(Accounts)]
+pub struct CancelUnbond<'info> {
+    #[account(
+        seeds = [b"stake_config"],
+        bump = stake_config.bump
+    )]
+    pub stake_config: Account<'info, StakeConfig>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"stake_operations_config",
+            stake_config.key().as_ref(),
+        ],
+        bump = stake_operations_config.bump,
+    )]
+    pub stake_operations_config: Account<'info, StakeOperationsConfig>,
+
+    #[account(
+        mut,
+        close = signer,
+        seeds = [b"ticket", signer.key().as_ref()],
+        bump,
+        constraint = ticket.owner == signer.key() @ CustomErrorCode::InvalidTicketOwner
+    )]
+    pub ticket: Account<'info, UnbondingTicket>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+}
+
+/// Read-only stake mint for views over stake token accounts passed as remaining accounts.
+#[derive(Accounts)]
+pub struct StakeMintView<'info> {
+    #[account(
+        seeds = [b"stake_config"],
+        bump = stake_config.bump
+    )]
+    pub stake_config: Account<'info, StakeConfig>,
+
+    #[account(
+        constraint = stake_config.mint == mint.key() @ CustomErrorCode::InvalidMint
+    )]
+    pub mint: Account<'info, Mint>,
+}
+
+/// Read-only vault configuration for auditing token accounts passed as remaining accounts.
+#[derive(Accounts)]
+pub struct VaultOwnershipView<'info> {
+    #[account(
+        seeds = [b"stake_config"],
+        bump = stake_config.bump
+    )]
+    pub stake_config: Account<'info, StakeConfig>,
+
+    #[account(
+        seeds = [
+            b"stake_vault_token_account_config",
+            stake_config.key().as_ref(),
+        ],
+        bump = stake_vault_token_account_config.bump,
+    )]
+    pub stake_vault_token_account_config: Account<'info, StakeVaultTokenAccountConfig>,
+
+    #[account(
+        seeds = [b"stake_operations_config", stake_config.key().as_ref()],
+        bump = stake_operations_config.bump,
+    )]
+    pub stake_operations_config: Account<'info, StakeOperationsConfig>,
+
+    /// CHECK: This is a PDA that acts as vault authority, validated by seeds constraint
+    #[account(
+        seeds = [b"vault_authority"],
+        bump
+    )]
+    pub vault_authority: UncheckedAccount<'info>,
+}
+
+// admin publishes rewards
+#[derive(Accounts)]
+#[instruction(id: u32, amount: u64)]
+pub struct PublishRewards<'info> {
+    #[account(
+        seeds = [b"stake_config"], 
+        bump = stake_config.bump
+    )]
+    pub stake_config: Box<Account<'info, StakeConfig>>,
+
+    /// hastra vault-mint program's executable. Declared before mint_config so a spoofed program
+    /// fails with InvalidMintProgram rather than on mint_config's seeds.
+    /// CHECK: must be StakeOperationsConfig.allowed_mint_program
+    #[account(
+        executable,
+        constraint = mint_program.key() == stake_operations_config.allowed_mint_program @ CustomErrorCode::InvalidMintProgram
+    )]
+    pub mint_program: AccountInfo<'info>,
+
+    /// vault-mint's config. Its `mint` (wYLDS) must be this program's vault token so that both
+    /// programs agree on the token that rewards are minted in.
+    #[account(
+        seeds = [b"config"], 
+        bump = mint_config.bump,
+        seeds::program = mint_program.key(),
+        constraint = *mint_config.to_account_info().owner == mint_program.key() @ CustomErrorCode::InvalidMintProgramOwner,
+        constraint = mint_config.mint == stake_config.vault @ CustomErrorCode::RewardsMintMustBeVaultMint
+    )]
+    pub mint_config: Box<Account<'info, vault_mint::state::Config>>,
+
+    /// PDA that proves this call is from this staking program.
+    /// Signed during the CPI to vault-mint. Only this program can produce a valid
+    /// signer for this address because it is derived from crate::id().
+    /// CHECK: This is a PDA derived from this program's id, validated by seeds
+    #[account(
+        seeds = [b"external_mint_authority"],
+        bump
+    )]
+    pub external_mint_authority: UncheckedAccount<'info>,
+
+    /// This program's own account, passed to vault-mint as the calling_program identifier
+    /// so vault-mint can verify the caller against its allowed-program list.
+    /// CHECK: Address is constrained to this program's deployed id
+    #[account(constraint = this_program.key() == crate::id() @ CustomErrorCode::InvalidAuthority)]
+    pub this_program: AccountInfo<'info>,
+
+    /// The AllowedExternalMintPrograms PDA from vault-mint, passed through during the CPI to
+    /// vault-mint::external_program_mint. vault-mint uses it to authorize callers beyond the
+    /// legacy single-program field. This account is required here and in vault-mint, so it
+    /// must already exist on-chain (typically created during vault-mint upgrade / migration
+    /// via register_allowed_external_mint_program, which uses init_if_needed) before
+    /// publish_rewards can succeed. Until any programs are registered, account data may be
+    /// empty or too short to deserialize; vault-mint then treats the extended list as empty
+    /// and only the legacy config field applies.
+    /// CHECK: Derived from vault-mint's config; list membership is enforced in vault-mint
+    #[account(
+        seeds = [b"allowed_external_mint_programs", mint_config.key().as_ref()],
+        seeds::program = mint_program.key(),
+        bump,
+    )]
+    pub vault_mint_allowed_external_programs: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    /// Rewards are paid in the vault token (wYLDS), not the stake token: vault-mint mints new
+    /// wYLDS straight into the vault token account, raising the assets behind every PRIME share.
+    /// So the "rewards mint" is this program's vault mint, which is vault-mint's share mint.
+    #[account(
+        mut,
+        constraint = rewards_mint.key() == stake_config.vault @ CustomErrorCode::RewardsMintMustBeVaultMint,
+        constraint = rewards_mint.mint_authority.unwrap() == rewards_mint_authority.key() @ CustomErrorCode::InvalidMintAuthority
+    )]
+    pub rewards_mint: Box<Account<'info, Mint>>,
+
+    /// CHECK: This is a PDA that acts as mint authority, validated by seeds constraint
+    #[account(
+        seeds = [b"mint_authority"],
+        seeds::program = mint_program.key(),
+        bump,
+    )]
+    pub rewards_mint_authority: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [
+            b"stake_vault_token_account_config",
+            stake_config.key().as_ref(),
+        ],
+        bump = stake_vault_token_account_config.bump,
+    )]
+    pub stake_vault_token_account_config: Box<Account<'info, StakeVaultTokenAccountConfig>>,
+
+    #[account(
+        mut,
+        token::mint = stake_config.vault,
+        constraint = vault_token_account.mint == stake_config.vault @ CustomErrorCode::InvalidVaultMint,
+        constraint = vault_token_account.key() == stake_vault_token_account_config.vault_token_account @ CustomErrorCode::InvalidVaultTokenAccount,
+        constraint = vault_token_account.owner == stake_vault_token_account_config.vault_authority @ CustomErrorCode::InvalidVaultAuthority
+    )]
+    pub vault_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: This is a PDA that acts as vault authority, validated by seeds constraint
+    #[account(
+        seeds = [b"vault_authority"],
+        bump,
+        constraint = vault_authority.key() == stake_vault_token_account_config.vault_authority @ CustomErrorCode::InvalidVaultAuthority
+    )]
+    pub vault_authority: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        constraint = mint.key() == stake_config.mint @ CustomErrorCode::InvalidMint
+    )]
+    pub mint: Box<Account<'info, Mint>>,
+
+    /// Reward record PDA to prevent duplicates
+    #[account(
+        init,
+        payer = admin,
+        space = RewardPublicationRecord::LEN,
+        seeds = [
+            b"reward_record",
+            id.to_le_bytes().as_ref(),
+            amount.to_le_bytes().as_ref(),
+        ],
+        bump
+    )]
+    pub reward_record: Box<Account<'info, RewardPublicationRecord>>,
 
     /// Reward cap config — must exist (see `initialize_stake_reward_config`).
     #[account(
@@ -477,55 +1402,975 @@ pub struct PublishRewards<'info> {
             b"stake_reward_config",
             stake_config.key().as_ref(),
         ],
-        bump = stake_reward_config.bump,
+        bump = stake_reward_config.bump,
+    )]
+    pub stake_reward_config: Box<Account<'info, StakeRewardConfig>>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"stake_operations_config",
+            stake_config.key().as_ref(),
+        ],
+        bump = stake_operations_config.bump,
+    )]
+    pub stake_operations_config: Box<Account<'info, StakeOperationsConfig>>,
+
+    /// Per-admin publication budgets — must exist (see `initialize_stake_reward_policy_config`).
+    #[account(
+        mut,
+        seeds = [
+            b"stake_reward_policy_config",
+            stake_config.key().as_ref(),
+        ],
+        bump = stake_reward_policy_config.bump,
+    )]
+    pub stake_reward_policy_config: Box<Account<'info, StakeRewardPolicyConfig>>,
+
+    pub system_program: Program<'info, System>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+// admin publishes rewards out of the pre-funded reward reserve instead of minting via vault-mint
+#[derive(Accounts)]
+#[instruction(id: u32, amount: u64)]
+pub struct PublishRewardsFromReserve<'info> {
+    #[account(
+        seeds = [b"stake_config"],
+        bump = stake_config.bump
+    )]
+    pub stake_config: Box<Account<'info, StakeConfig>>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [
+            b"stake_vault_token_account_config",
+            stake_config.key().as_ref(),
+        ],
+        bump = stake_vault_token_account_config.bump,
+    )]
+    pub stake_vault_token_account_config: Box<Account<'info, StakeVaultTokenAccountConfig>>,
+
+    #[account(
+        mut,
+        token::mint = stake_config.vault,
+        constraint = vault_token_account.mint == stake_config.vault @ CustomErrorCode::InvalidVaultMint,
+        constraint = vault_token_account.key() == stake_vault_token_account_config.vault_token_account @ CustomErrorCode::InvalidVaultTokenAccount,
+        constraint = vault_token_account.owner == stake_vault_token_account_config.vault_authority @ CustomErrorCode::InvalidVaultAuthority
+    )]
+    pub vault_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// StakeOperationsConfig.reward_reserve_token_account, owned by the vault authority.
+    #[account(
+        mut,
+        constraint = reward_reserve_token_account.key() == stake_operations_config.reward_reserve_token_account @ CustomErrorCode::InvalidRewardReserveTokenAccount
+    )]
+    pub reward_reserve_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: This is a PDA that acts as vault authority, validated by seeds constraint
+    #[account(
+        seeds = [b"vault_authority"],
+        bump,
+        constraint = vault_authority.key() == stake_vault_token_account_config.vault_authority @ CustomErrorCode::InvalidVaultAuthority
+    )]
+    pub vault_authority: UncheckedAccount<'info>,
+
+    #[account(
+        constraint = mint.key() == stake_config.mint @ CustomErrorCode::InvalidMint
+    )]
+    pub mint: Box<Account<'info, Mint>>,
+
+    /// Reward record PDA to prevent duplicates (shared with publish_rewards)
+    #[account(
+        init,
+        payer = admin,
+        space = RewardPublicationRecord::LEN,
+        seeds = [
+            b"reward_record",
+            id.to_le_bytes().as_ref(),
+            amount.to_le_bytes().as_ref(),
+        ],
+        bump
+    )]
+    pub reward_record: Box<Account<'info, RewardPublicationRecord>>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"stake_reward_config",
+            stake_config.key().as_ref(),
+        ],
+        bump = stake_reward_config.bump,
+    )]
+    pub stake_reward_config: Box<Account<'info, StakeRewardConfig>>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"stake_operations_config",
+            stake_config.key().as_ref(),
+        ],
+        bump = stake_operations_config.bump,
+    )]
+    pub stake_operations_config: Box<Account<'info, StakeOperationsConfig>>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"stake_reward_policy_config",
+            stake_config.key().as_ref(),
+        ],
+        bump = stake_reward_policy_config.bump,
+    )]
+    pub stake_reward_policy_config: Box<Account<'info, StakeRewardPolicyConfig>>,
+
+    pub system_program: Program<'info, System>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+// rewards admin commits a holder-balance snapshot root
+#[derive(Accounts)]
+#[instruction(merkle_root: [u8; 32], total_shares_snapshot: u64, slot: u64)]
+pub struct CommitSnapshot<'info> {
+    #[account(
+        seeds = [b"stake_config"],
+        bump = stake_config.bump
+    )]
+    pub stake_config: Box<Account<'info, StakeConfig>>,
+
+    /// Holds events_digest, updated by every emitted event.
+    #[account(
+        mut,
+        seeds = [b"stake_operations_config", stake_config.key().as_ref()],
+        bump = stake_operations_config.bump,
+    )]
+    pub stake_operations_config: Box<Account<'info, StakeOperationsConfig>>,
+
+    #[account(
+        seeds = [
+            b"stake_reward_policy_config",
+            stake_config.key().as_ref(),
+        ],
+        bump = stake_reward_policy_config.bump,
+    )]
+    pub stake_reward_policy_config: Box<Account<'info, StakeRewardPolicyConfig>>,
+
+    /// One snapshot per slot; committing the same slot again fails.
+    #[account(
+        init,
+        payer = admin,
+        space = SnapshotRoot::LEN,
+        seeds = [
+            b"snapshot_root",
+            stake_config.key().as_ref(),
+            slot.to_le_bytes().as_ref(),
+        ],
+        bump
+    )]
+    pub snapshot_root: Account<'info, SnapshotRoot>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Read-only access to a committed SnapshotRoot.
+#[derive(Accounts)]
+pub struct SnapshotView<'info> {
+    #[account(
+        seeds = [b"stake_config"],
+        bump = stake_config.bump
+    )]
+    pub stake_config: Account<'info, StakeConfig>,
+
+    #[account(
+        seeds = [
+            b"snapshot_root",
+            stake_config.key().as_ref(),
+            snapshot_root.slot.to_le_bytes().as_ref(),
+        ],
+        bump = snapshot_root.bump,
+    )]
+    pub snapshot_root: Account<'info, SnapshotRoot>,
+}
+
+// rewards admin publishes a PRIME airdrop root
+#[derive(Accounts)]
+#[instruction(merkle_root: [u8; 32])]
+pub struct PublishStakeReward<'info> {
+    #[account(
+        seeds = [b"stake_config"],
+        bump = stake_config.bump
+    )]
+    pub stake_config: Box<Account<'info, StakeConfig>>,
+
+    /// Holds paused_operations (PAUSE_PUBLISH_REWARDS) and events_digest.
+    #[account(
+        mut,
+        seeds = [b"stake_operations_config", stake_config.key().as_ref()],
+        bump = stake_operations_config.bump,
+    )]
+    pub stake_operations_config: Box<Account<'info, StakeOperationsConfig>>,
+
+    /// Publication is charged against the reward caps, cooldown and per-admin budgets.
+    #[account(
+        mut,
+        seeds = [
+            b"stake_reward_policy_config",
+            stake_config.key().as_ref(),
+        ],
+        bump = stake_reward_policy_config.bump,
+    )]
+    pub stake_reward_policy_config: Box<Account<'info, StakeRewardPolicyConfig>>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"stake_reward_config",
+            stake_config.key().as_ref(),
+        ],
+        bump = stake_reward_config.bump,
+    )]
+    pub stake_reward_config: Box<Account<'info, StakeRewardConfig>>,
+
+    #[account(
+        seeds = [
+            b"stake_vault_token_account_config",
+            stake_config.key().as_ref(),
+        ],
+        bump = stake_vault_token_account_config.bump,
+    )]
+    pub stake_vault_token_account_config: Box<Account<'info, StakeVaultTokenAccountConfig>>,
+
+    #[account(
+        constraint = vault_token_account.key() == stake_vault_token_account_config.vault_token_account @ CustomErrorCode::InvalidVaultTokenAccount,
+    )]
+    pub vault_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        constraint = mint.key() == stake_config.mint @ CustomErrorCode::InvalidMint
+    )]
+    pub mint: Box<Account<'info, Mint>>,
+
+    /// Prices total_shares into vault tokens for the caps.
+    #[account(
+        seeds = [b"stake_price_config", stake_config.key().as_ref()],
+        bump = stake_price_config.bump,
+    )]
+    pub stake_price_config: Box<Account<'info, StakePriceConfig>>,
+
+    /// One distribution per root; publishing the same root again fails.
+    #[account(
+        init,
+        payer = admin,
+        space = StakeRewardDistribution::LEN,
+        seeds = [
+            b"stake_reward",
+            stake_config.key().as_ref(),
+            merkle_root.as_ref(),
+        ],
+        bump
+    )]
+    pub stake_reward_distribution: Account<'info, StakeRewardDistribution>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+// holder claims extra PRIME from a published stake reward
+#[derive(Accounts)]
+pub struct ClaimStakeReward<'info> {
+    #[account(
+        seeds = [b"stake_config"],
+        bump = stake_config.bump
+    )]
+    pub stake_config: Box<Account<'info, StakeConfig>>,
+
+    /// Holds max_total_shares and events_digest.
+    #[account(
+        mut,
+        seeds = [b"stake_operations_config", stake_config.key().as_ref()],
+        bump = stake_operations_config.bump,
+    )]
+    pub stake_operations_config: Box<Account<'info, StakeOperationsConfig>>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"stake_reward",
+            stake_config.key().as_ref(),
+            stake_reward_distribution.merkle_root.as_ref(),
+        ],
+        bump = stake_reward_distribution.bump
+    )]
+    pub stake_reward_distribution: Account<'info, StakeRewardDistribution>,
+
+    /// Existence marks the claim as done; a second claim fails to create it.
+    #[account(
+        init,
+        payer = signer,
+        space = ClaimRecord::LEN,
+        seeds = [
+            b"stake_reward_claim",
+            stake_reward_distribution.key().as_ref(),
+            signer.key().as_ref(),
+        ],
+        bump
+    )]
+    pub claim_record: Account<'info, ClaimRecord>,
+
+    #[account(
+        mut,
+        constraint = mint.key() == stake_config.mint @ CustomErrorCode::InvalidMint
+    )]
+    pub mint: Account<'info, Mint>,
+
+    /// CHECK: This is a PDA that acts as mint authority, validated by seeds constraint
+    #[account(
+        seeds = [b"mint_authority"],
+        bump,
+        constraint = mint_authority.key() == mint.mint_authority.unwrap() @ CustomErrorCode::InvalidMintAuthority
+    )]
+    pub mint_authority: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        constraint = user_mint_token_account.mint == stake_config.mint @ CustomErrorCode::InvalidRecipientMint,
+        constraint = user_mint_token_account.owner == signer.key() @ CustomErrorCode::InvalidRecipientOwner
+    )]
+    pub user_mint_token_account: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+// admin publishes a zero-amount epoch marker (no mint CPI)
+#[derive(Accounts)]
+#[instruction(id: u32)]
+pub struct PublishEpochMarker<'info> {
+    #[account(
+        seeds = [b"stake_config"],
+        bump = stake_config.bump
+    )]
+    pub stake_config: Box<Account<'info, StakeConfig>>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [
+            b"stake_vault_token_account_config",
+            stake_config.key().as_ref(),
+        ],
+        bump = stake_vault_token_account_config.bump,
+    )]
+    pub stake_vault_token_account_config: Box<Account<'info, StakeVaultTokenAccountConfig>>,
+
+    #[account(
+        token::mint = stake_config.vault,
+        constraint = vault_token_account.key() == stake_vault_token_account_config.vault_token_account @ CustomErrorCode::InvalidVaultTokenAccount,
+    )]
+    pub vault_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        constraint = mint.key() == stake_config.mint @ CustomErrorCode::InvalidMint
+    )]
+    pub mint: Box<Account<'info, Mint>>,
+
+    /// Same PDA as publish_rewards with amount 0, so a marker id cannot be reused as a marker.
+    #[account(
+        init,
+        payer = admin,
+        space = RewardPublicationRecord::LEN,
+        seeds = [
+            b"reward_record",
+            id.to_le_bytes().as_ref(),
+            0u64.to_le_bytes().as_ref(),
+        ],
+        bump
+    )]
+    pub reward_record: Box<Account<'info, RewardPublicationRecord>>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"stake_operations_config",
+            stake_config.key().as_ref(),
+        ],
+        bump = stake_operations_config.bump,
+    )]
+    pub stake_operations_config: Box<Account<'info, StakeOperationsConfig>>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"stake_reward_policy_config",
+            stake_config.key().as_ref(),
+        ],
+        bump = stake_reward_policy_config.bump,
+    )]
+    pub stake_reward_policy_config: Box<Account<'info, StakeRewardPolicyConfig>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ConversionView<'info> {
+    #[account(
+        seeds = [b"stake_config"],
+        bump = stake_config.bump
+    )]
+    pub stake_config: Account<'info, StakeConfig>,
+
+    #[account(
+        constraint = mint.key() == stake_config.mint @ CustomErrorCode::InvalidMint
+    )]
+    pub mint: Account<'info, Mint>,
+
+    /// Vault token mint (e.g. wYLDS); read for its decimals.
+    #[account(
+        constraint = vault_mint.key() == stake_config.vault @ CustomErrorCode::InvalidVaultMint
+    )]
+    pub vault_mint: Account<'info, Mint>,
+
+    #[account(
+        constraint = vault_token_account.mint == stake_config.vault @ CustomErrorCode::InvalidVaultMint,
+        constraint = vault_token_account.owner == vault_authority.key() @ CustomErrorCode::InvalidVaultAuthority
+    )]
+    pub vault_token_account: Account<'info, TokenAccount>,
+
+    /// CHECK: This is a PDA that acts as vault authority, validated by seeds constraint
+    #[account(
+        seeds = [b"vault_authority"],
+        bump
+    )]
+    pub vault_authority: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"stake_price_config", stake_config.key().as_ref()],
+        bump = stake_price_config.bump,
+    )]
+    pub stake_price_config: Account<'info, StakePriceConfig>,
+
+    /// Read for the rate override.
+    #[account(
+        seeds = [b"stake_operations_config", stake_config.key().as_ref()],
+        bump = stake_operations_config.bump,
+    )]
+    pub stake_operations_config: Account<'info, StakeOperationsConfig>,
+}
+
+/// Read-only accounts for health_check. The vault token account is matched by address only so
+/// that a wrong owner is reported in the status mask instead of failing the call.
+#[derive(Accounts)]
+pub struct HealthCheck<'info> {
+    #[account(
+        seeds = [b"stake_config"],
+        bump = stake_config.bump
+    )]
+    pub stake_config: Account<'info, StakeConfig>,
+
+    #[account(
+        seeds = [
+            b"stake_vault_token_account_config",
+            stake_config.key().as_ref(),
+        ],
+        bump = stake_vault_token_account_config.bump,
+    )]
+    pub stake_vault_token_account_config: Account<'info, StakeVaultTokenAccountConfig>,
+
+    #[account(
+        constraint = vault_token_account.key() == stake_vault_token_account_config.vault_token_account @ CustomErrorCode::InvalidVaultTokenAccount,
+    )]
+    pub vault_token_account: Account<'info, TokenAccount>,
+
+    /// CHECK: This is a PDA that acts as vault authority, validated by seeds constraint
+    #[account(
+        seeds = [b"vault_authority"],
+        bump
+    )]
+    pub vault_authority: UncheckedAccount<'info>,
+
+    #[account(
+        constraint = mint.key() == stake_config.mint @ CustomErrorCode::InvalidMint
+    )]
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        seeds = [b"stake_price_config", stake_config.key().as_ref()],
+        bump = stake_price_config.bump,
+    )]
+    pub stake_price_config: Account<'info, StakePriceConfig>,
+
+    #[account(
+        seeds = [b"stake_operations_config", stake_config.key().as_ref()],
+        bump = stake_operations_config.bump,
+    )]
+    pub stake_operations_config: Account<'info, StakeOperationsConfig>,
+}
+
+/// Read-only price, rate override and a holder's stake token account for the
+/// assets_for_target_shares view.
+#[derive(Accounts)]
+pub struct TargetSharesView<'info> {
+    #[account(
+        seeds = [b"stake_config"],
+        bump = stake_config.bump
+    )]
+    pub stake_config: Account<'info, StakeConfig>,
+
+    #[account(
+        seeds = [b"stake_price_config", stake_config.key().as_ref()],
+        bump = stake_price_config.bump,
+    )]
+    pub stake_price_config: Account<'info, StakePriceConfig>,
+
+    /// The holder's stake token (PRIME) account; its balance is the starting point.
+    #[account(
+        constraint = user_mint_token_account.mint == stake_config.mint @ CustomErrorCode::InvalidMint
+    )]
+    pub user_mint_token_account: Account<'info, TokenAccount>,
+
+    /// Holds rate_override_1e9, which the quote honours like deposit does.
+    #[account(
+        seeds = [b"stake_operations_config", stake_config.key().as_ref()],
+        bump = stake_operations_config.bump,
+    )]
+    pub stake_operations_config: Account<'info, StakeOperationsConfig>,
+}
+
+// ========== PRICE CONFIG ACCOUNT CONTEXTS ==========
+
+/// Creates the StakePriceConfig PDA.
+/// Only callable by the program upgrade authority.
+/// Must be called once after deployment before any deposit/redeem can occur.
+#[derive(Accounts)]
+pub struct InitializePriceConfig<'info> {
+    #[account(
+        seeds = [b"stake_config"],
+        bump = stake_config.bump
+    )]
+    pub stake_config: Account<'info, StakeConfig>,
+
+    #[account(
+        init,
+        payer = signer,
+        space = StakePriceConfig::LEN,
+        seeds = [
+            b"stake_price_config",
+            stake_config.key().as_ref(),
+        ],
+        bump
+    )]
+    pub stake_price_config: Account<'info, StakePriceConfig>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    /// CHECK: This is the program data account that contains the update authority
+    #[account(
+        constraint = program_data.key() == get_program_data_address(&crate::id()) @ CustomErrorCode::InvalidProgramData
+    )]
+    pub program_data: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Updates configuration parameters on an existing StakePriceConfig.
+/// Only callable by the program upgrade authority.
+/// Does NOT reset price or price_timestamp.
+#[derive(Accounts)]
+pub struct UpdatePriceConfig<'info> {
+    #[account(
+        seeds = [b"stake_config"],
+        bump = stake_config.bump
+    )]
+    pub stake_config: Account<'info, StakeConfig>,
+
+    /// Holds events_digest, updated by every emitted event.
+    #[account(
+        mut,
+        seeds = [b"stake_operations_config", stake_config.key().as_ref()],
+        bump = stake_operations_config.bump,
+    )]
+    pub stake_operations_config: Account<'info, StakeOperationsConfig>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"stake_price_config",
+            stake_config.key().as_ref(),
+        ],
+        bump = stake_price_config.bump,
+    )]
+    pub stake_price_config: Account<'info, StakePriceConfig>,
+
+    pub signer: Signer<'info>,
+
+    /// CHECK: This is the program data account that contains the update authority
+    #[account(
+        constraint = program_data.key() == get_program_data_address(&crate::id()) @ CustomErrorCode::InvalidProgramData
+    )]
+    pub program_data: UncheckedAccount<'info>,
+}
+
+/// Submits a signed Chainlink Data Streams report for on-chain verification.
+/// On success, stores the verified price and the report’s observations timestamp (staleness anchor) in
+/// StakePriceConfig.
+/// Only callable by rewards administrators.
+#[derive(Accounts)]
+pub struct VerifyPrice<'info> {
+    #[account(
+        seeds = [b"stake_config"],
+        bump = stake_config.bump
+    )]
+    pub stake_config: Account<'info, StakeConfig>,
+
+    /// Holds events_digest, updated by every emitted event.
+    #[account(
+        mut,
+        seeds = [b"stake_operations_config", stake_config.key().as_ref()],
+        bump = stake_operations_config.bump,
+    )]
+    pub stake_operations_config: Account<'info, StakeOperationsConfig>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"stake_price_config",
+            stake_config.key().as_ref(),
+        ],
+        bump = stake_price_config.bump,
+    )]
+    pub stake_price_config: Account<'info, StakePriceConfig>,
+
+    /// CHECK: Validated by the Chainlink verifier program during CPI
+    pub chainlink_verifier_account: AccountInfo<'info>,
+
+    /// CHECK: Validated by the Chainlink verifier program during CPI
+    pub chainlink_access_controller: AccountInfo<'info>,
+
+    /// CHECK: PDA derived from the report's feed ID; validated by the Chainlink verifier program
+    pub chainlink_config_account: UncheckedAccount<'info>,
+
+    /// CHECK: Must match stake_price_config.chainlink_program — enforced in processor
+    pub chainlink_program: AccountInfo<'info>,
+
+    pub signer: Signer<'info>,
+}
+
+/// FOR TESTING ONLY — directly sets the stored price and timestamp on StakePriceConfig.
+/// This bypasses the Chainlink CPI and allows localnet tests to set an arbitrary price.
+/// Access is restricted to the program upgrade authority (same as initialize).
+/// DO NOT USE IN PRODUCTION — use verify_price with a real Chainlink report instead.
+#[cfg(feature = "testing")]
+#[derive(Accounts)]
+pub struct SetPriceForTesting<'info> {
+    #[account(
+        seeds = [b"stake_config"],
+        bump = stake_config.bump
+    )]
+    pub stake_config: Account<'info, StakeConfig>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"stake_price_config",
+            stake_config.key().as_ref(),
+        ],
+        bump = stake_price_config.bump,
+    )]
+    pub stake_price_config: Account<'info, StakePriceConfig>,
+
+    pub signer: Signer<'info>,
+
+    /// CHECK: This is the program data account that contains the update authority
+    #[account(
+        constraint = program_data.key() == get_program_data_address(&crate::id()) @ CustomErrorCode::InvalidProgramData
+    )]
+    pub program_data: UncheckedAccount<'info>,
+}
+
+/// FOR TESTING ONLY — sets StakeConfig.paused without touching StakeOperationsConfig, the way a
+/// pause from a program version without the operations config left it.
+/// Access is restricted to the program upgrade authority.
+#[cfg(feature = "testing")]
+#[derive(Accounts)]
+pub struct SetPausedForTesting<'info> {
+    #[account(
+        mut,
+        seeds = [b"stake_config"],
+        bump = stake_config.bump
+    )]
+    pub stake_config: Account<'info, StakeConfig>,
+
+    pub signer: Signer<'info>,
+
+    /// CHECK: This is the program data account that contains the update authority
+    #[account(
+        constraint = program_data.key() == get_program_data_address(&crate::id()) @ CustomErrorCode::InvalidProgramData
+    )]
+    pub program_data: UncheckedAccount<'info>,
+}
+
+/// Creates the StakeRewardConfig PDA with protocol default caps and cooldown.
+/// Must be called once before `publish_rewards` can enforce limits.
+/// Only callable by the program upgrade authority.
+#[derive(Accounts)]
+pub struct InitializeStakeRewardConfig<'info> {
+    #[account(
+        seeds = [b"stake_config"],
+        bump = stake_config.bump
+    )]
+    pub stake_config: Account<'info, StakeConfig>,
+
+    #[account(
+        init,
+        payer = signer,
+        space = StakeRewardConfig::LEN,
+        seeds = [
+            b"stake_reward_config",
+            stake_config.key().as_ref(),
+        ],
+        bump
     )]
-    pub stake_reward_config: Box<Account<'info, StakeRewardConfig>>,
+    pub stake_reward_config: Account<'info, StakeRewardConfig>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    /// CHECK: This is the program data account that contains the update authority
+    #[account(
+        constraint = program_data.key() == get_program_data_address(&crate::id()) @ CustomErrorCode::InvalidProgramData
+    )]
+    pub program_data: UncheckedAccount<'info>,
 
     pub system_program: Program<'info, System>,
+}
 
-    pub token_program: Program<'info, Token>,
+/// Updates max_reward_bps on an existing StakeRewardConfig.
+/// Only callable by the program upgrade authority.
+#[derive(Accounts)]
+pub struct UpdateMaxRewardBps<'info> {
+    #[account(
+        seeds = [b"stake_config"],
+        bump = stake_config.bump
+    )]
+    pub stake_config: Account<'info, StakeConfig>,
+
+    /// Holds events_digest, updated by every emitted event.
+    #[account(
+        mut,
+        seeds = [b"stake_operations_config", stake_config.key().as_ref()],
+        bump = stake_operations_config.bump,
+    )]
+    pub stake_operations_config: Account<'info, StakeOperationsConfig>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"stake_reward_config",
+            stake_config.key().as_ref(),
+        ],
+        bump = stake_reward_config.bump,
+    )]
+    pub stake_reward_config: Account<'info, StakeRewardConfig>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    /// CHECK: This is the program data account that contains the update authority
+    #[account(
+        constraint = program_data.key() == get_program_data_address(&crate::id()) @ CustomErrorCode::InvalidProgramData
+    )]
+    pub program_data: UncheckedAccount<'info>,
 }
 
+/// Updates max_period_rewards on an existing StakeRewardConfig.
+/// Only callable by the program upgrade authority.
 #[derive(Accounts)]
-pub struct ConversionView<'info> {
+pub struct UpdateMaxPeriodRewards<'info> {
+    #[account(
+        seeds = [b"stake_config"],
+        bump = stake_config.bump
+    )]
+    pub stake_config: Account<'info, StakeConfig>,
+
+    /// Holds events_digest, updated by every emitted event.
+    #[account(
+        mut,
+        seeds = [b"stake_operations_config", stake_config.key().as_ref()],
+        bump = stake_operations_config.bump,
+    )]
+    pub stake_operations_config: Account<'info, StakeOperationsConfig>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"stake_reward_config",
+            stake_config.key().as_ref(),
+        ],
+        bump = stake_reward_config.bump,
+    )]
+    pub stake_reward_config: Account<'info, StakeRewardConfig>,
+
+    #[account()]
+    pub signer: Signer<'info>,
+
+    /// CHECK: This is the program data account that contains the update authority
+    #[account(
+        constraint = program_data.key() == get_program_data_address(&crate::id()) @ CustomErrorCode::InvalidProgramData
+    )]
+    pub program_data: UncheckedAccount<'info>,
+}
+
+/// Updates reward_period_seconds on an existing StakeRewardConfig.
+/// Only callable by the program upgrade authority.
+#[derive(Accounts)]
+pub struct UpdateRewardPeriodSeconds<'info> {
+    #[account(
+        seeds = [b"stake_config"],
+        bump = stake_config.bump
+    )]
+    pub stake_config: Account<'info, StakeConfig>,
+
+    /// Holds events_digest, updated by every emitted event.
+    #[account(
+        mut,
+        seeds = [b"stake_operations_config", stake_config.key().as_ref()],
+        bump = stake_operations_config.bump,
+    )]
+    pub stake_operations_config: Account<'info, StakeOperationsConfig>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"stake_reward_config",
+            stake_config.key().as_ref(),
+        ],
+        bump = stake_reward_config.bump,
+    )]
+    pub stake_reward_config: Account<'info, StakeRewardConfig>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    /// CHECK: This is the program data account that contains the update authority
+    #[account(
+        constraint = program_data.key() == get_program_data_address(&crate::id()) @ CustomErrorCode::InvalidProgramData
+    )]
+    pub program_data: UncheckedAccount<'info>,
+}
+
+/// Updates max_total_rewards on an existing StakeRewardConfig.
+/// Only callable by the program upgrade authority.
+#[derive(Accounts)]
+pub struct UpdateMaxTotalRewards<'info> {
+    #[account(
+        seeds = [b"stake_config"],
+        bump = stake_config.bump
+    )]
+    pub stake_config: Account<'info, StakeConfig>,
+
+    /// Holds events_digest, updated by every emitted event.
+    #[account(
+        mut,
+        seeds = [b"stake_operations_config", stake_config.key().as_ref()],
+        bump = stake_operations_config.bump,
+    )]
+    pub stake_operations_config: Account<'info, StakeOperationsConfig>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"stake_reward_config",
+            stake_config.key().as_ref(),
+        ],
+        bump = stake_reward_config.bump,
+    )]
+    pub stake_reward_config: Account<'info, StakeRewardConfig>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    /// CHECK: This is the program data account that contains the update authority
+    #[account(
+        constraint = program_data.key() == get_program_data_address(&crate::id()) @ CustomErrorCode::InvalidProgramData
+    )]
+    pub program_data: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct Donate<'info> {
     #[account(
         seeds = [b"stake_config"],
         bump = stake_config.bump
     )]
-    pub stake_config: Account<'info, StakeConfig>,
+    pub stake_config: Account<'info, StakeConfig>,
+
+    #[account(
+        seeds = [
+            b"stake_vault_token_account_config",
+            stake_config.key().as_ref(),
+        ],
+        bump = stake_vault_token_account_config.bump,
+    )]
+    pub stake_vault_token_account_config: Account<'info, StakeVaultTokenAccountConfig>,
+
+    #[account(
+        mut,
+        token::mint = stake_config.vault,
+        constraint = vault_token_account.mint == stake_config.vault @ CustomErrorCode::InvalidVaultMint,
+        constraint = vault_token_account.key() == stake_vault_token_account_config.vault_token_account @ CustomErrorCode::InvalidVaultTokenAccount,
+        constraint = vault_token_account.owner == stake_vault_token_account_config.vault_authority @ CustomErrorCode::InvalidVaultAuthority
+    )]
+    pub vault_token_account: Box<Account<'info, TokenAccount>>,
 
     #[account(
         constraint = mint.key() == stake_config.mint @ CustomErrorCode::InvalidMint
     )]
     pub mint: Account<'info, Mint>,
 
-    #[account(
-        constraint = vault_token_account.mint == stake_config.vault @ CustomErrorCode::InvalidVaultMint,
-        constraint = vault_token_account.owner == vault_authority.key() @ CustomErrorCode::InvalidVaultAuthority
-    )]
-    pub vault_token_account: Account<'info, TokenAccount>,
+    pub donor: Signer<'info>,
 
-    /// CHECK: This is a PDA that acts as vault authority, validated by seeds constraint
     #[account(
-        seeds = [b"vault_authority"],
-        bump
+        mut,
+        token::mint = stake_config.vault,
+        constraint = donor_vault_token_account.mint == stake_config.vault @ CustomErrorCode::InvalidVaultMint,
+        constraint = donor_vault_token_account.owner == donor.key() @ CustomErrorCode::InvalidTokenOwner
     )]
-    pub vault_authority: UncheckedAccount<'info>,
+    pub donor_vault_token_account: Box<Account<'info, TokenAccount>>,
 
     #[account(
-        seeds = [b"stake_price_config", stake_config.key().as_ref()],
-        bump = stake_price_config.bump,
+        mut,
+        seeds = [
+            b"stake_operations_config",
+            stake_config.key().as_ref(),
+        ],
+        bump = stake_operations_config.bump,
     )]
-    pub stake_price_config: Account<'info, StakePriceConfig>,
+    pub stake_operations_config: Box<Account<'info, StakeOperationsConfig>>,
+
+    pub token_program: Program<'info, Token>,
 }
 
-// ========== PRICE CONFIG ACCOUNT CONTEXTS ==========
+// ========== OPERATIONS CONFIG ACCOUNT CONTEXTS ==========
 
-/// Creates the StakePriceConfig PDA.
+/// Creates the StakeOperationsConfig PDA.
+/// Must be called once after deployment before deposit, redeem or publish_rewards can proceed.
 /// Only callable by the program upgrade authority.
-/// Must be called once after deployment before any deposit/redeem can occur.
 #[derive(Accounts)]
-pub struct InitializePriceConfig<'info> {
+pub struct InitializeStakeOperationsConfig<'info> {
     #[account(
         seeds = [b"stake_config"],
         bump = stake_config.bump
@@ -535,14 +2380,14 @@ pub struct InitializePriceConfig<'info> {
     #[account(
         init,
         payer = signer,
-        space = StakePriceConfig::LEN,
+        space = StakeOperationsConfig::LEN,
         seeds = [
-            b"stake_price_config",
+            b"stake_operations_config",
             stake_config.key().as_ref(),
         ],
         bump
     )]
-    pub stake_price_config: Account<'info, StakePriceConfig>,
+    pub stake_operations_config: Account<'info, StakeOperationsConfig>,
 
     #[account(mut)]
     pub signer: Signer<'info>,
@@ -556,11 +2401,10 @@ pub struct InitializePriceConfig<'info> {
     pub system_program: Program<'info, System>,
 }
 
-/// Updates configuration parameters on an existing StakePriceConfig.
+/// Updates an existing StakeOperationsConfig.
 /// Only callable by the program upgrade authority.
-/// Does NOT reset price or price_timestamp.
 #[derive(Accounts)]
-pub struct UpdatePriceConfig<'info> {
+pub struct UpdateStakeOperationsConfig<'info> {
     #[account(
         seeds = [b"stake_config"],
         bump = stake_config.bump
@@ -570,12 +2414,12 @@ pub struct UpdatePriceConfig<'info> {
     #[account(
         mut,
         seeds = [
-            b"stake_price_config",
+            b"stake_operations_config",
             stake_config.key().as_ref(),
         ],
-        bump = stake_price_config.bump,
+        bump = stake_operations_config.bump,
     )]
-    pub stake_price_config: Account<'info, StakePriceConfig>,
+    pub stake_operations_config: Account<'info, StakeOperationsConfig>,
 
     pub signer: Signer<'info>,
 
@@ -586,12 +2430,9 @@ pub struct UpdatePriceConfig<'info> {
     pub program_data: UncheckedAccount<'info>,
 }
 
-/// Submits a signed Chainlink Data Streams report for on-chain verification.
-/// On success, stores the verified price and the report’s observations timestamp (staleness anchor) in
-/// StakePriceConfig.
-/// Only callable by rewards administrators.
+// Admin sets the reserve account publish_rewards_from_reserve pays rewards from.
 #[derive(Accounts)]
-pub struct VerifyPrice<'info> {
+pub struct UpdateRewardReserve<'info> {
     #[account(
         seeds = [b"stake_config"],
         bump = stake_config.bump
@@ -599,52 +2440,92 @@ pub struct VerifyPrice<'info> {
     pub stake_config: Account<'info, StakeConfig>,
 
     #[account(
-        mut,
         seeds = [
-            b"stake_price_config",
+            b"stake_vault_token_account_config",
             stake_config.key().as_ref(),
         ],
-        bump = stake_price_config.bump,
+        bump = stake_vault_token_account_config.bump,
     )]
-    pub stake_price_config: Account<'info, StakePriceConfig>,
-
-    /// CHECK: Validated by the Chainlink verifier program during CPI
-    pub chainlink_verifier_account: AccountInfo<'info>,
+    pub stake_vault_token_account_config: Account<'info, StakeVaultTokenAccountConfig>,
 
-    /// CHECK: Validated by the Chainlink verifier program during CPI
-    pub chainlink_access_controller: AccountInfo<'info>,
+    #[account(
+        mut,
+        seeds = [
+            b"stake_operations_config",
+            stake_config.key().as_ref(),
+        ],
+        bump = stake_operations_config.bump,
+    )]
+    pub stake_operations_config: Account<'info, StakeOperationsConfig>,
 
-    /// CHECK: PDA derived from the report's feed ID; validated by the Chainlink verifier program
-    pub chainlink_config_account: UncheckedAccount<'info>,
+    /// Must be a vault-token account owned by the vault authority, separate from the vault and
+    /// the insurance fund.
+    #[account(
+        constraint = reward_reserve_token_account.mint == stake_config.vault @ CustomErrorCode::InvalidVaultMint,
+        constraint = reward_reserve_token_account.owner == vault_authority.key() @ CustomErrorCode::InvalidVaultAuthority,
+        constraint = reward_reserve_token_account.key() != stake_vault_token_account_config.vault_token_account @ CustomErrorCode::InvalidRewardReserveTokenAccount,
+        constraint = reward_reserve_token_account.key() != stake_operations_config.insurance_token_account @ CustomErrorCode::InvalidRewardReserveTokenAccount
+    )]
+    pub reward_reserve_token_account: Account<'info, TokenAccount>,
 
-    /// CHECK: Must match stake_price_config.chainlink_program — enforced in processor
-    pub chainlink_program: AccountInfo<'info>,
+    /// CHECK: This is a PDA that acts as vault authority, validated by seeds constraint
+    #[account(
+        seeds = [b"vault_authority"],
+        bump
+    )]
+    pub vault_authority: UncheckedAccount<'info>,
 
     pub signer: Signer<'info>,
+
+    /// CHECK: This is the program data account that contains the update authority
+    #[account(
+        constraint = program_data.key() == get_program_data_address(&crate::id()) @ CustomErrorCode::InvalidProgramData
+    )]
+    pub program_data: UncheckedAccount<'info>,
 }
 
-/// FOR TESTING ONLY — directly sets the stored price and timestamp on StakePriceConfig.
-/// This bypasses the Chainlink CPI and allows localnet tests to set an arbitrary price.
-/// Access is restricted to the program upgrade authority (same as initialize).
-/// DO NOT USE IN PRODUCTION — use verify_price with a real Chainlink report instead.
-#[cfg(feature = "testing")]
+// Admin sets the insurance split and the account that receives it.
 #[derive(Accounts)]
-pub struct SetPriceForTesting<'info> {
+pub struct UpdateInsuranceConfig<'info> {
     #[account(
         seeds = [b"stake_config"],
         bump = stake_config.bump
     )]
     pub stake_config: Account<'info, StakeConfig>,
 
+    #[account(
+        seeds = [
+            b"stake_vault_token_account_config",
+            stake_config.key().as_ref(),
+        ],
+        bump = stake_vault_token_account_config.bump,
+    )]
+    pub stake_vault_token_account_config: Account<'info, StakeVaultTokenAccountConfig>,
+
     #[account(
         mut,
         seeds = [
-            b"stake_price_config",
+            b"stake_operations_config",
             stake_config.key().as_ref(),
         ],
-        bump = stake_price_config.bump,
+        bump = stake_operations_config.bump,
     )]
-    pub stake_price_config: Account<'info, StakePriceConfig>,
+    pub stake_operations_config: Account<'info, StakeOperationsConfig>,
+
+    /// Must be a vault-token account owned by the vault authority, separate from the vault.
+    #[account(
+        constraint = insurance_token_account.mint == stake_config.vault @ CustomErrorCode::InvalidVaultMint,
+        constraint = insurance_token_account.owner == vault_authority.key() @ CustomErrorCode::InvalidVaultAuthority,
+        constraint = insurance_token_account.key() != stake_vault_token_account_config.vault_token_account @ CustomErrorCode::InvalidInsuranceTokenAccount
+    )]
+    pub insurance_token_account: Account<'info, TokenAccount>,
+
+    /// CHECK: This is a PDA that acts as vault authority, validated by seeds constraint
+    #[account(
+        seeds = [b"vault_authority"],
+        bump
+    )]
+    pub vault_authority: UncheckedAccount<'info>,
 
     pub signer: Signer<'info>,
 
@@ -655,11 +2536,9 @@ pub struct SetPriceForTesting<'info> {
     pub program_data: UncheckedAccount<'info>,
 }
 
-/// Creates the StakeRewardConfig PDA with protocol default caps and cooldown.
-/// Must be called once before `publish_rewards` can enforce limits.
-/// Only callable by the program upgrade authority.
+// Admin moves insurance funds into the vault to cover a shortfall.
 #[derive(Accounts)]
-pub struct InitializeStakeRewardConfig<'info> {
+pub struct DrawInsurance<'info> {
     #[account(
         seeds = [b"stake_config"],
         bump = stake_config.bump
@@ -667,18 +2546,43 @@ pub struct InitializeStakeRewardConfig<'info> {
     pub stake_config: Account<'info, StakeConfig>,
 
     #[account(
-        init,
-        payer = signer,
-        space = StakeRewardConfig::LEN,
         seeds = [
-            b"stake_reward_config",
+            b"stake_vault_token_account_config",
+            stake_config.key().as_ref(),
+        ],
+        bump = stake_vault_token_account_config.bump,
+    )]
+    pub stake_vault_token_account_config: Account<'info, StakeVaultTokenAccountConfig>,
+
+    #[account(
+        mut,
+        constraint = vault_token_account.key() == stake_vault_token_account_config.vault_token_account @ CustomErrorCode::InvalidVaultTokenAccount,
+    )]
+    pub vault_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"stake_operations_config",
             stake_config.key().as_ref(),
         ],
+        bump = stake_operations_config.bump,
+    )]
+    pub stake_operations_config: Account<'info, StakeOperationsConfig>,
+
+    #[account(
+        mut,
+        constraint = insurance_token_account.key() == stake_operations_config.insurance_token_account @ CustomErrorCode::InvalidInsuranceTokenAccount
+    )]
+    pub insurance_token_account: Account<'info, TokenAccount>,
+
+    /// CHECK: This is a PDA that acts as vault authority, validated by seeds constraint
+    #[account(
+        seeds = [b"vault_authority"],
         bump
     )]
-    pub stake_reward_config: Account<'info, StakeRewardConfig>,
+    pub vault_authority: UncheckedAccount<'info>,
 
-    #[account(mut)]
     pub signer: Signer<'info>,
 
     /// CHECK: This is the program data account that contains the update authority
@@ -687,13 +2591,13 @@ pub struct InitializeStakeRewardConfig<'info> {
     )]
     pub program_data: UncheckedAccount<'info>,
 
-    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
 }
 
-/// Updates max_reward_bps on an existing StakeRewardConfig.
-/// Only callable by the program upgrade authority.
+/// Read-only accounts for views that report on the vault's assets (idle + deployed).
+/// Unlike ConversionView, the vault token account is pinned to StakeVaultTokenAccountConfig.
 #[derive(Accounts)]
-pub struct UpdateMaxRewardBps<'info> {
+pub struct VaultAssetsView<'info> {
     #[account(
         seeds = [b"stake_config"],
         bump = stake_config.bump
@@ -701,59 +2605,106 @@ pub struct UpdateMaxRewardBps<'info> {
     pub stake_config: Account<'info, StakeConfig>,
 
     #[account(
-        mut,
         seeds = [
-            b"stake_reward_config",
+            b"stake_vault_token_account_config",
             stake_config.key().as_ref(),
         ],
-        bump = stake_reward_config.bump,
+        bump = stake_vault_token_account_config.bump,
     )]
-    pub stake_reward_config: Account<'info, StakeRewardConfig>,
+    pub stake_vault_token_account_config: Account<'info, StakeVaultTokenAccountConfig>,
 
-    #[account(mut)]
-    pub signer: Signer<'info>,
+    #[account(
+        constraint = vault_token_account.mint == stake_config.vault @ CustomErrorCode::InvalidVaultMint,
+        constraint = vault_token_account.key() == stake_vault_token_account_config.vault_token_account @ CustomErrorCode::InvalidVaultTokenAccount,
+    )]
+    pub vault_token_account: Account<'info, TokenAccount>,
 
-    /// CHECK: This is the program data account that contains the update authority
     #[account(
-        constraint = program_data.key() == get_program_data_address(&crate::id()) @ CustomErrorCode::InvalidProgramData
+        constraint = mint.key() == stake_config.mint @ CustomErrorCode::InvalidMint
     )]
-    pub program_data: UncheckedAccount<'info>,
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        seeds = [b"stake_price_config", stake_config.key().as_ref()],
+        bump = stake_price_config.bump,
+    )]
+    pub stake_price_config: Account<'info, StakePriceConfig>,
+
+    #[account(
+        seeds = [b"stake_operations_config", stake_config.key().as_ref()],
+        bump = stake_operations_config.bump,
+    )]
+    pub stake_operations_config: Account<'info, StakeOperationsConfig>,
 }
 
-/// Updates max_period_rewards on an existing StakeRewardConfig.
-/// Only callable by the program upgrade authority.
+/// Read-only access to StakeConfig alone, for views that only report configured addresses.
 #[derive(Accounts)]
-pub struct UpdateMaxPeriodRewards<'info> {
+pub struct StakeConfigView<'info> {
     #[account(
         seeds = [b"stake_config"],
         bump = stake_config.bump
     )]
     pub stake_config: Account<'info, StakeConfig>,
+}
 
+/// Read-only access to every config holding a timing setting, for the timing_params view.
+#[derive(Accounts)]
+pub struct TimingParamsView<'info> {
     #[account(
-        mut,
-        seeds = [
-            b"stake_reward_config",
-            stake_config.key().as_ref(),
-        ],
+        seeds = [b"stake_config"],
+        bump = stake_config.bump
+    )]
+    pub stake_config: Account<'info, StakeConfig>,
+
+    #[account(
+        seeds = [b"stake_price_config", stake_config.key().as_ref()],
+        bump = stake_price_config.bump,
+    )]
+    pub stake_price_config: Account<'info, StakePriceConfig>,
+
+    #[account(
+        seeds = [b"stake_operations_config", stake_config.key().as_ref()],
+        bump = stake_operations_config.bump,
+    )]
+    pub stake_operations_config: Account<'info, StakeOperationsConfig>,
+
+    #[account(
+        seeds = [b"stake_reward_config", stake_config.key().as_ref()],
         bump = stake_reward_config.bump,
     )]
     pub stake_reward_config: Account<'info, StakeRewardConfig>,
+}
 
-    #[account()]
-    pub signer: Signer<'info>,
+/// Read-only access to the vault token account config, for the expected_vault_token_account view.
+#[derive(Accounts)]
+pub struct VaultTokenAccountConfigView<'info> {
+    #[account(
+        seeds = [b"stake_config"],
+        bump = stake_config.bump
+    )]
+    pub stake_config: Account<'info, StakeConfig>,
 
-    /// CHECK: This is the program data account that contains the update authority
     #[account(
-        constraint = program_data.key() == get_program_data_address(&crate::id()) @ CustomErrorCode::InvalidProgramData
+        seeds = [
+            b"stake_vault_token_account_config",
+            stake_config.key().as_ref(),
+        ],
+        bump = stake_vault_token_account_config.bump,
     )]
-    pub program_data: UncheckedAccount<'info>,
+    pub stake_vault_token_account_config: Account<'info, StakeVaultTokenAccountConfig>,
 }
 
-/// Updates reward_period_seconds on an existing StakeRewardConfig.
+/// No accounts: the seed_scheme view only reports a program constant.
+#[derive(Accounts)]
+pub struct SeedSchemeView {}
+
+// ========== REWARD POLICY CONFIG ACCOUNT CONTEXTS ==========
+
+/// Creates the StakeRewardPolicyConfig PDA.
+/// Must be called once after deployment before publish_rewards can proceed.
 /// Only callable by the program upgrade authority.
 #[derive(Accounts)]
-pub struct UpdateRewardPeriodSeconds<'info> {
+pub struct InitializeStakeRewardPolicyConfig<'info> {
     #[account(
         seeds = [b"stake_config"],
         bump = stake_config.bump
@@ -761,14 +2712,16 @@ pub struct UpdateRewardPeriodSeconds<'info> {
     pub stake_config: Account<'info, StakeConfig>,
 
     #[account(
-        mut,
+        init,
+        payer = signer,
+        space = StakeRewardPolicyConfig::LEN,
         seeds = [
-            b"stake_reward_config",
+            b"stake_reward_policy_config",
             stake_config.key().as_ref(),
         ],
-        bump = stake_reward_config.bump,
+        bump
     )]
-    pub stake_reward_config: Account<'info, StakeRewardConfig>,
+    pub stake_reward_policy_config: Account<'info, StakeRewardPolicyConfig>,
 
     #[account(mut)]
     pub signer: Signer<'info>,
@@ -778,29 +2731,38 @@ pub struct UpdateRewardPeriodSeconds<'info> {
         constraint = program_data.key() == get_program_data_address(&crate::id()) @ CustomErrorCode::InvalidProgramData
     )]
     pub program_data: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
 }
 
-/// Updates max_total_rewards on an existing StakeRewardConfig.
+/// Updates an existing StakeRewardPolicyConfig.
 /// Only callable by the program upgrade authority.
 #[derive(Accounts)]
-pub struct UpdateMaxTotalRewards<'info> {
+pub struct UpdateStakeRewardPolicyConfig<'info> {
     #[account(
         seeds = [b"stake_config"],
         bump = stake_config.bump
     )]
     pub stake_config: Account<'info, StakeConfig>,
 
+    /// Holds events_digest, updated by every emitted event.
+    #[account(
+        mut,
+        seeds = [b"stake_operations_config", stake_config.key().as_ref()],
+        bump = stake_operations_config.bump,
+    )]
+    pub stake_operations_config: Account<'info, StakeOperationsConfig>,
+
     #[account(
         mut,
         seeds = [
-            b"stake_reward_config",
+            b"stake_reward_policy_config",
             stake_config.key().as_ref(),
         ],
-        bump = stake_reward_config.bump,
+        bump = stake_reward_policy_config.bump,
     )]
-    pub stake_reward_config: Account<'info, StakeRewardConfig>,
+    pub stake_reward_policy_config: Account<'info, StakeRewardPolicyConfig>,
 
-    #[account(mut)]
     pub signer: Signer<'info>,
 
     /// CHECK: This is the program data account that contains the update authority
@@ -809,3 +2771,22 @@ pub struct UpdateMaxTotalRewards<'info> {
     )]
     pub program_data: UncheckedAccount<'info>,
 }
+
+/// Read-only access to StakeRewardPolicyConfig for views.
+#[derive(Accounts)]
+pub struct RewardPolicyView<'info> {
+    #[account(
+        seeds = [b"stake_config"],
+        bump = stake_config.bump
+    )]
+    pub stake_config: Account<'info, StakeConfig>,
+
+    #[account(
+        seeds = [
+            b"stake_reward_policy_config",
+            stake_config.key().as_ref(),
+        ],
+        bump = stake_reward_policy_config.bump,
+    )]
+    pub stake_reward_policy_config: Account<'info, StakeRewardPolicyConfig>,
+}
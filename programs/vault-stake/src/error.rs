@@ -86,4 +86,130 @@ pub enum CustomErrorCode {
     InvalidMaxTotalRewards = 48,
     #[msg("Chainlink report has invalid timestamp ordering (e.g. observations before valid_from)")]
     InvalidReportTimestamps = 49,
+    #[msg("Reward amount exceeds the rewards administrator's remaining publication budget")]
+    AdminBudgetExceeded = 50,
+    #[msg("Rewards mint must be the stake vault token mint (vault-mint's wYLDS mint)")]
+    RewardsMintMustBeVaultMint = 51,
+    #[msg("Redeem would leave idle vault liquidity below the configured reserve floor")]
+    ReserveFloorBreached = 52,
+    #[msg("Invalid min reserve BPS: must be at most 10_000")]
+    InvalidMinReserveBps = 53,
+    #[msg("Hook program is required but was not passed as the first remaining account")]
+    HookProgramMissing = 54,
+    #[msg("Vault token account must be empty at initialization")]
+    VaultNotEmpty = 55,
+    #[msg("Rewards administrator is temporarily disabled")]
+    RewardsAdminDisabled = 56,
+    #[msg("Commit-reveal deposits are disabled")]
+    CommitRevealDisabled = 57,
+    #[msg("Deposit reveal is too early: the commit-reveal delay has not elapsed")]
+    RevealTooEarly = 58,
+    #[msg("Revealed amount and salt do not match the deposit commitment")]
+    CommitmentMismatch = 59,
+    #[msg("Vault token account is frozen by the vault mint's freeze authority; withdrawals are blocked")]
+    VaultAccountFrozen = 60,
+    #[msg("Redeem invariant violated: transferred assets do not match the burned shares")]
+    RedeemMathInconsistent = 61,
+    #[msg("Invalid rate override: must be greater than 0 (use None to clear)")]
+    InvalidRateOverride = 62,
+    #[msg("Too many accounts passed: at most 64 token accounts per call")]
+    TooManyAccounts = 63,
+    #[msg("Redeem exceeds the circuit breaker's maximum single-transaction outflow")]
+    SingleOutflowLimitExceeded = 64,
+    #[msg("Invalid max single outflow BPS: must be at most 10_000")]
+    InvalidMaxSingleOutflowBps = 65,
+    #[msg(
+        "Deposit invariant violated: vault balance plus deployed assets do not match total assets"
+    )]
+    DepositAccountingMismatch = 66,
+    #[msg("Redeem cooldown active: wait before redeeming again")]
+    RedeemCooldownActive = 67,
+    #[msg("Invalid redeem cooldown: must not be negative")]
+    InvalidRedeemCooldown = 68,
+    #[msg("Restriction reason too long: at most 64 bytes")]
+    RestrictionReasonTooLong = 69,
+    #[msg("Token account is already restricted")]
+    AccountAlreadyRestricted = 70,
+    #[msg("Token account is not restricted")]
+    AccountNotRestricted = 71,
+    #[msg("Invalid recipient owner: the redeem destination must be owned by the signer")]
+    InvalidRecipientOwner = 72,
+    #[msg("Invalid recipient mint: the redeem destination must hold the vault token")]
+    InvalidRecipientMint = 73,
+    #[msg("Invalid insurance BPS: must be less than 10_000")]
+    InvalidInsuranceBps = 74,
+    #[msg("Invalid insurance token account")]
+    InvalidInsuranceTokenAccount = 75,
+    #[msg("Insurance token account required: insurance_bps is set")]
+    InsuranceTokenAccountRequired = 76,
+    #[msg("Insufficient insurance funds")]
+    InsufficientInsuranceFunds = 77,
+    #[msg("Reward id not monotonic: must be greater than the last published id")]
+    RewardIdNotMonotonic = 78,
+    #[msg("Reward id space exhausted: u32::MAX has been published")]
+    RewardIdSpaceExhausted = 79,
+    #[msg("Invalid max pause duration: must be between 1 second and 30 days")]
+    InvalidMaxPauseDuration = 80,
+    #[msg("Admin list version mismatch: the list changed since it was read")]
+    AdminListVersionMismatch = 81,
+    #[msg("Invalid vesting duration: must be greater than 0")]
+    InvalidVestingDuration = 82,
+    #[msg("Vesting schedule active: claim every vested share before starting a new one")]
+    VestingScheduleActive = 83,
+    #[msg("Nothing vested: no shares are claimable yet")]
+    NothingVested = 84,
+    #[msg("Invalid mint program: not the allowed vault-mint program")]
+    InvalidMintProgram = 85,
+    #[msg("Max total shares exceeded: the deposit would push the stake supply above the cap")]
+    MaxSharesExceeded = 86,
+    #[msg("Invalid snapshot: the slot must not be in the future and the root must be non-zero")]
+    InvalidSnapshot = 87,
+    #[msg("Operation paused: its bit is set in paused_operations")]
+    OperationPaused = 88,
+    #[msg("Invalid pause mask: unknown operation bits")]
+    InvalidPauseMask = 89,
+    #[msg("Deposit amount too large: the vault balance or share supply would exceed u64::MAX; deposit less")]
+    DepositAmountTooLarge = 90,
+    #[msg("Duplicate administrator: each key may appear only once in an administrator list")]
+    DuplicateAdministrator = 91,
+    #[msg("Invalid administrator: the default (zero) pubkey cannot be an administrator")]
+    InvalidAdministrator = 92,
+    #[msg("Redeem amount is too small - would not receive any vault tokens")]
+    RedeemTooSmall = 93,
+    #[msg("Invalid stake reward: the root must be non-zero and total_shares positive")]
+    InvalidStakeReward = 94,
+    #[msg("Invalid Merkle proof")]
+    InvalidMerkleProof = 95,
+    #[msg("Stake reward exhausted: the claim exceeds the distribution's unclaimed shares")]
+    StakeRewardExhausted = 96,
+    #[msg(
+        "Insufficient supply for rewards: the stake mint's supply is below min_supply_for_rewards"
+    )]
+    InsufficientSupplyForRewards = 97,
+    #[msg("Cannot remove the last administrator: the administrator list must not be empty")]
+    CannotRemoveLastAdministrator = 98,
+    #[msg("Swap program not allowed: it must be StakeOperationsConfig.swap_program")]
+    SwapProgramNotAllowed = 99,
+    #[msg("The swap spent more of the input token than amount_in")]
+    ZapInputExceeded = 100,
+    #[msg("Zap slippage exceeded: the swap delivered less than min_vault_out")]
+    ZapSlippageExceeded = 101,
+    #[msg("The relayer is not the approved delegate of the user's vault token account")]
+    InvalidRelayerDelegate = 102,
+    #[msg("The user's approval to the relayer does not cover the deposit amount")]
+    InsufficientDelegatedAmount = 103,
+    #[msg("The reward record is still within reward_record_retention and cannot be pruned")]
+    RewardRecordWithinRetention = 104,
+    #[msg("Invalid reward record retention: must not be negative")]
+    InvalidRewardRecordRetention = 105,
+    #[msg("External mint disabled: publish rewards from the reserve instead")]
+    ExternalMintDisabled = 106,
+    #[msg("Invalid reward reserve token account")]
+    InvalidRewardReserveTokenAccount = 107,
+    #[msg("Insufficient reward reserve funds")]
+    InsufficientRewardReserve = 108,
+    #[msg("The unbonding ticket does not belong to the signer")]
+    InvalidTicketOwner = 109,
+    #[msg("Redeem state account is required while a redeem cooldown is configured")]
+    RedeemStateRequired = 110,
 }
@@ -1,17 +1,32 @@
 use anchor_lang::prelude::*;
 
+// Share pricing has no virtual share/asset offsets, so the totals below are the true vault
+// metrics; indexers should use them directly rather than adjusting mint_supply/vault_balance.
 #[event]
 pub struct DepositEvent {
     pub user: Pubkey,
+    /// Full amount taken from the user, including insurance_amount.
     pub deposit_amount: u64,
     pub minted_amount: u64,
     pub mint: Pubkey,
+    /// Stake mint supply before this deposit's shares were minted.
     pub mint_supply: u64,
     pub vault: Pubkey,
+    /// Idle vault token balance after the deposit.
     pub vault_balance: u64,
+    /// vault_balance + deployed assets, after the deposit.
     pub total_assets: u64,
+    /// mint_supply + minted_amount: stake mint supply after the deposit.
     pub total_shares: u64,
+    /// Slot of this deposit: StakeOperationsConfig.last_totals_update_slot after it.
     pub totals_last_update_slot: u64,
+    /// Liquidity health after the deposit: idle vault balance / total assets, in BPS.
+    pub idle_liquidity_bps: u16,
+    /// Part of deposit_amount sent to the insurance fund (insurance_bps); mints no shares.
+    pub insurance_amount: u64,
+    /// True when no shares were outstanding before this deposit (mint_supply == 0). It is priced
+    /// like any other deposit, so its rate matches the exchange_rate view up to flooring.
+    pub first_deposit: bool,
 }
 
 #[event]
@@ -19,20 +34,36 @@ pub struct RedeemEvent {
     pub user: Pubkey,
     pub mint: Pubkey,
     pub requested_mint_amount: u64,
+    /// Stake mint supply before this redeem's shares were burned.
     pub mint_supply: u64,
     pub vault: Pubkey,
     pub redeemed_vault_amount: u64,
+    /// Idle vault token balance before the redeem's transfer.
     pub vault_balance: u64,
     pub shares_burned: u64,
+    /// vault_balance - redeemed_vault_amount + deployed assets, after the redeem.
     pub total_assets: u64,
+    /// mint_supply - shares_burned: stake mint supply after the redeem.
     pub total_shares: u64,
+    /// Slot of this redeem: StakeOperationsConfig.last_totals_update_slot after it.
     pub totals_last_update_slot: u64,
+    /// Rent lamports returned to the user by closing a legacy unbonding ticket (0 if none).
+    pub reclaimed_rent_lamports: u64,
+}
+
+/// A legacy unbonding ticket was closed without redeeming (cancel_unbond).
+#[event]
+pub struct UnbondCancelledEvent {
+    pub owner: Pubkey,
+    pub requested_amount: u64,
+    pub mint: Pubkey,
 }
 
 #[event]
 pub struct RewardsPublished {
     pub admin: Pubkey,
     pub amount: u64,
+    /// Pubkey::default() when the rewards came from the reserve (publish_rewards_from_reserve).
     pub mint_program: Pubkey,
     pub vault_token_account: Pubkey,
     pub mint: Pubkey,
@@ -93,3 +124,315 @@ pub struct PriceInvalidated {
     pub feed_id: [u8; 32],
     pub price_scale: u64,
 }
+
+#[event]
+pub struct DeployedAssetsReported {
+    pub admin: Pubkey,
+    pub old_value: u64,
+    pub new_value: u64,
+    pub stake_config: Pubkey,
+}
+
+#[event]
+pub struct MinReserveBpsUpdated {
+    pub admin: Pubkey,
+    pub old_bps: u16,
+    pub new_bps: u16,
+    pub stake_config: Pubkey,
+}
+
+#[event]
+pub struct CommitRevealUpdated {
+    pub admin: Pubkey,
+    pub enabled: bool,
+    pub delay_slots: u64,
+    pub stake_config: Pubkey,
+}
+
+#[event]
+pub struct DepositCommitted {
+    pub user: Pubkey,
+    pub commitment: [u8; 32],
+    pub committed_slot: u64,
+    pub reveal_after_slot: u64,
+}
+
+#[event]
+pub struct RateOverrideUpdated {
+    pub admin: Pubkey,
+    pub old_rate_1e9: Option<u64>,
+    pub new_rate_1e9: Option<u64>,
+    pub active: bool,
+    pub stake_config: Pubkey,
+}
+
+#[event]
+pub struct CircuitBreakerUpdated {
+    pub admin: Pubkey,
+    pub old_max_single_outflow_bps: u16,
+    pub new_max_single_outflow_bps: u16,
+    pub auto_pause: bool,
+    pub stake_config: Pubkey,
+}
+
+#[event]
+pub struct AccountRestricted {
+    pub token_account: Pubkey,
+    pub owner: Pubkey,
+    pub reason: String,
+    pub restricted_by: Pubkey,
+    pub restricted_at: i64,
+}
+
+#[event]
+pub struct RestrictionLifted {
+    pub token_account: Pubkey,
+    pub owner: Pubkey,
+    pub reason: String,
+    pub restricted_by: Pubkey,
+    pub restricted_at: i64,
+    pub lifted_by: Pubkey,
+    pub lifted_at: i64,
+}
+
+#[event]
+pub struct RedeemCooldownUpdated {
+    pub admin: Pubkey,
+    pub old_seconds: i64,
+    pub new_seconds: i64,
+    pub stake_config: Pubkey,
+}
+
+#[event]
+pub struct RewardIdEnforcementUpdated {
+    pub enforce_monotonic_ids: bool,
+    pub next_expected_reward_id: u64,
+    pub stake_config: Pubkey,
+}
+
+#[event]
+pub struct AllowedMintProgramUpdated {
+    pub admin: Pubkey,
+    pub old_mint_program: Pubkey,
+    pub new_mint_program: Pubkey,
+    pub stake_config: Pubkey,
+}
+
+#[event]
+pub struct MaxTotalSharesUpdated {
+    pub admin: Pubkey,
+    pub old_max_total_shares: Option<u64>,
+    pub new_max_total_shares: Option<u64>,
+    pub stake_config: Pubkey,
+}
+
+#[event]
+pub struct ExternalMintEnabledUpdated {
+    pub admin: Pubkey,
+    pub enabled: bool,
+    pub stake_config: Pubkey,
+}
+
+#[event]
+pub struct RewardReserveUpdated {
+    pub admin: Pubkey,
+    pub old_reward_reserve_token_account: Pubkey,
+    pub new_reward_reserve_token_account: Pubkey,
+    pub stake_config: Pubkey,
+}
+
+#[event]
+pub struct RewardReserveDrawn {
+    pub id: u32,
+    pub amount: u64,
+    pub reward_reserve_token_account: Pubkey,
+    pub reserve_balance: u64,
+    pub stake_config: Pubkey,
+}
+
+#[event]
+pub struct RewardRecordRetentionUpdated {
+    pub admin: Pubkey,
+    pub old_reward_record_retention: i64,
+    pub new_reward_record_retention: i64,
+    pub stake_config: Pubkey,
+}
+
+#[event]
+pub struct RewardRecordPruned {
+    pub id: u32,
+    pub amount: u64,
+    pub published_at: i64,
+    pub record: Pubkey,
+    pub rent_recipient: Pubkey,
+    pub stake_config: Pubkey,
+}
+
+#[event]
+pub struct SwapProgramUpdated {
+    pub admin: Pubkey,
+    pub old_swap_program: Pubkey,
+    pub new_swap_program: Pubkey,
+    pub stake_config: Pubkey,
+}
+
+#[event]
+pub struct RelayedDeposit {
+    pub user: Pubkey,
+    pub relayer: Pubkey,
+    pub amount: u64,
+    pub stake_config: Pubkey,
+}
+
+#[event]
+pub struct ZapDeposited {
+    pub user: Pubkey,
+    pub swap_program: Pubkey,
+    pub input_mint: Pubkey,
+    pub input_spent: u64,
+    pub vault_received: u64,
+    pub stake_config: Pubkey,
+}
+
+#[event]
+pub struct NonemptyAdminRequirementsUpdated {
+    pub admin: Pubkey,
+    pub require_nonempty_freeze_admins: bool,
+    pub require_nonempty_rewards_admins: bool,
+    pub stake_config: Pubkey,
+}
+
+#[event]
+pub struct MinSupplyForRewardsUpdated {
+    pub admin: Pubkey,
+    pub old_min_supply_for_rewards: u64,
+    pub new_min_supply_for_rewards: u64,
+    pub stake_config: Pubkey,
+}
+
+#[event]
+pub struct MaxPauseDurationUpdated {
+    pub admin: Pubkey,
+    pub old_seconds: i64,
+    pub new_seconds: i64,
+    pub stake_config: Pubkey,
+}
+
+#[event]
+pub struct InsuranceConfigUpdated {
+    pub admin: Pubkey,
+    pub old_bps: u16,
+    pub new_bps: u16,
+    pub insurance_token_account: Pubkey,
+    pub stake_config: Pubkey,
+}
+
+#[event]
+pub struct InsuranceDrawn {
+    pub admin: Pubkey,
+    pub amount: u64,
+    pub insurance_balance: u64, // insurance token account balance after the draw
+    pub vault_balance: u64,     // vault token account balance after the draw
+    pub stake_config: Pubkey,
+}
+
+#[event]
+pub struct CircuitBreakerTripped {
+    pub user: Pubkey,
+    pub requested_assets: u64,
+    pub total_assets: u64,
+    pub outflow_bps: u16,
+    pub max_single_outflow_bps: u16,
+    pub stake_config: Pubkey,
+}
+
+#[event]
+pub struct HookUpdated {
+    pub admin: Pubkey,
+    pub old_hook_program: Pubkey,
+    pub new_hook_program: Pubkey,
+    pub require_hook_account: bool,
+    pub stake_config: Pubkey,
+}
+
+#[event]
+pub struct DonationEvent {
+    pub donor: Pubkey,
+    pub amount: u64,
+    pub vault: Pubkey,
+    pub vault_balance: u64,
+    pub total_assets: u64,
+    pub total_shares: u64,
+    /// Assets backing one share after the donation, scaled by 1e9 (0 when no shares exist).
+    pub assets_per_share: u64,
+    pub total_donated: u64,
+    pub totals_last_update_slot: u64,
+}
+
+#[event]
+pub struct RewardBudgetUpdated {
+    pub admin: Pubkey,
+    pub old_budget: Option<u64>,
+    pub new_budget: u64,
+    pub stake_config: Pubkey,
+}
+
+#[event]
+pub struct RewardsAdminEnabledUpdated {
+    pub admin: Pubkey,
+    pub enabled: bool,
+    pub stake_config: Pubkey,
+}
+
+#[event]
+pub struct VestingDeposited {
+    pub user: Pubkey,
+    pub deposit_amount: u64,
+    pub vested_shares: u64, // shares minted into the vesting escrow
+    pub start_ts: i64,
+    pub duration_seconds: i64,
+    pub stake_config: Pubkey,
+}
+
+#[event]
+pub struct VestedClaimed {
+    pub user: Pubkey,
+    pub amount: u64,         // shares released by this claim
+    pub claimed_shares: u64, // shares released so far
+    pub total_shares: u64,
+    pub stake_config: Pubkey,
+}
+
+#[event]
+pub struct SnapshotCommitted {
+    pub admin: Pubkey,
+    pub merkle_root: [u8; 32],
+    pub total_shares_snapshot: u64,
+    pub slot: u64,
+    pub stake_config: Pubkey,
+}
+
+#[event]
+pub struct PauseMaskUpdated {
+    pub admin: Pubkey,
+    pub old_mask: u8,
+    pub new_mask: u8,
+    pub stake_config: Pubkey,
+}
+
+#[event]
+pub struct StakeRewardPublished {
+    pub admin: Pubkey,
+    pub merkle_root: [u8; 32],
+    pub total_shares: u64,
+    pub stake_config: Pubkey,
+}
+
+#[event]
+pub struct StakeRewardClaimed {
+    pub user: Pubkey,
+    pub merkle_root: [u8; 32],
+    pub extra_shares: u64,
+    pub claimed_shares: u64, // distribution total claimed after this claim
+    pub stake_config: Pubkey,
+}
@@ -8,6 +8,25 @@ pub fn validate_program_update_authority(
     program_data_account: &UncheckedAccount,
     authority: &Signer,
 ) -> Result<()> {
+    match read_program_upgrade_authority(program_data_account)? {
+        Some(update_authority) => {
+            require!(
+                authority.key() == update_authority,
+                CustomErrorCode::InvalidUpgradeAuthority
+            );
+        }
+        None => {
+            return Err(CustomErrorCode::NoUpgradeAuthority.into());
+        }
+    }
+
+    Ok(())
+}
+
+/// Reads the upgrade authority from the program data account (None once the program is immutable).
+pub fn read_program_upgrade_authority(
+    program_data_account: &UncheckedAccount,
+) -> Result<Option<Pubkey>> {
     // Deserialize the program data account
     let program_data = program_data_account
         .try_borrow_data()
@@ -21,19 +40,7 @@ pub fn validate_program_update_authority(
         UpgradeableLoaderState::ProgramData {
             slot: _,
             upgrade_authority_address,
-        } => match upgrade_authority_address {
-            Some(update_authority) => {
-                require!(
-                    authority.key() == update_authority,
-                    CustomErrorCode::InvalidUpgradeAuthority
-                );
-            }
-            None => {
-                return Err(CustomErrorCode::NoUpgradeAuthority.into());
-            }
-        },
-        _ => return Err(CustomErrorCode::InvalidProgramData.into()),
+        } => Ok(upgrade_authority_address),
+        _ => Err(CustomErrorCode::InvalidProgramData.into()),
     }
-
-    Ok(())
 }
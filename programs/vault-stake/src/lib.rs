@@ -16,7 +16,7 @@ pub mod account_structs;
 ///
 /// 3. Withdrawal Flow:
 ///    - User calls redeem(amount) with the amount of PRIME to burn
-///    - Program computes wYLDS owed using virtual shares formula
+///    - Program computes wYLDS owed from the Chainlink price (wYLDS per PRIME)
 ///    - PRIME is burned; wYLDS is transferred to user immediately
 ///    - Any legacy unbonding ticket from v1 is automatically closed (rent returned)
 ///      when the optional ticket account is provided
@@ -47,12 +47,20 @@ pub mod vault_stake {
     /// Initializes the vault program with the required token configurations:
     /// - vault_mint: The token that users deposit (e.g., wYLDS)
     /// - stake_mint: The token users receive when staking (e.g., PRIME)
+    /// - require_empty_vault_on_init: reject a pre-funded vault token account
+    ///   (defaults to true when omitted)
     pub fn initialize(
         ctx: Context<Initialize>,
         freeze_administrators: Vec<Pubkey>,
         rewards_administrators: Vec<Pubkey>,
+        require_empty_vault_on_init: Option<bool>,
     ) -> Result<()> {
-        processor::initialize(ctx, freeze_administrators, rewards_administrators)
+        processor::initialize(
+            ctx,
+            freeze_administrators,
+            rewards_administrators,
+            require_empty_vault_on_init,
+        )
     }
 
     /// Pauses or unpauses the protocol operations:
@@ -61,6 +69,13 @@ pub mod vault_stake {
         processor::pause(ctx, pause)
     }
 
+    /// Pauses individual operations: `mask` holds PAUSE_* bits (deposit, unbond (reserved),
+    /// redeem, publish rewards, freeze); see the PAUSE_* constants for the instructions each bit
+    /// stops. Only callable by freeze administrators.
+    pub fn set_paused_operations(ctx: Context<Pause>, mask: u8) -> Result<()> {
+        processor::set_paused_operations(ctx, mask)
+    }
+
     /// Handles user deposits of vault tokens (e.g., wYLDS):
     /// - Transfers vault tokens to program vault account
     /// - Mints equivalent amount of stake tokens (e.g., PRIME) to user
@@ -68,19 +83,89 @@ pub mod vault_stake {
         processor::deposit(ctx, amount)
     }
 
+    /// Same as deposit, but creates the signer's stake token (PRIME) associated token account
+    /// if it does not exist yet, charging the rent to the signer.
+    pub fn deposit_with_ata(ctx: Context<DepositWithAta>, amount: u64) -> Result<()> {
+        processor::deposit_with_ata(ctx, amount)
+    }
+
+    /// Deposits `amount` of the user's vault tokens on their behalf: the relayer signs and pays
+    /// the fees as the delegate the user approved (SPL approve) on their vault token account,
+    /// and the shares are minted to the user's stake token account.
+    pub fn deposit_with_relayer(ctx: Context<DepositWithRelayer>, amount: u64) -> Result<()> {
+        processor::deposit_with_relayer(ctx, amount)
+    }
+
+    /// Swaps up to `amount_in` of an input token for the vault token through the allowlisted
+    /// swap program, then deposits everything the swap delivered (at least `min_vault_out`).
+    /// `swap_data` is the swap program's instruction data and the remaining accounts its
+    /// accounts. Does not run the deposit hook, so fails while a hook is required.
+    pub fn zap_deposit<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ZapDeposit<'info>>,
+        amount_in: u64,
+        min_vault_out: u64,
+        swap_data: Vec<u8>,
+    ) -> Result<()> {
+        processor::zap_deposit(ctx, amount_in, min_vault_out, swap_data)
+    }
+
+    /// Deposits like deposit, but mints the shares into the signer's vesting escrow, to be
+    /// released linearly over `vesting_duration` seconds by claim_vested.
+    pub fn deposit_vesting(
+        ctx: Context<DepositVesting>,
+        amount: u64,
+        vesting_duration: i64,
+    ) -> Result<()> {
+        processor::deposit_vesting(ctx, amount, vesting_duration)
+    }
+
+    /// Transfers the signer's vested, unclaimed shares from their vesting escrow.
+    pub fn claim_vested(ctx: Context<ClaimVested>) -> Result<()> {
+        processor::claim_vested(ctx)
+    }
+
+    /// Records a hashed deposit intent for commit-reveal deposits:
+    /// commitment = sha256(user || amount (u64 LE) || salt).
+    /// Requires commit-reveal to be enabled in StakeOperationsConfig.
+    pub fn commit_deposit(ctx: Context<CommitDeposit>, commitment: [u8; 32]) -> Result<()> {
+        processor::commit_deposit(ctx, commitment)
+    }
+
+    /// Executes a committed deposit after the configured slot delay, priced at the reveal slot.
+    /// Fails if amount and salt do not match the stored commitment.
+    pub fn reveal_deposit(ctx: Context<RevealDeposit>, amount: u64, salt: [u8; 32]) -> Result<()> {
+        processor::reveal_deposit(ctx, amount, salt)
+    }
+
     /// Redeems stake tokens (PRIME) for vault tokens (wYLDS):
     /// - Burns the specified amount of PRIME from the user's account
     /// - Transfers the proportional wYLDS from the vault to the user immediately
     /// - Optionally closes a legacy unbonding ticket (from v1) and returns rent to user
+    ///
+    /// If the circuit breaker is in auto-pause mode and the redeem exceeds
+    /// `max_single_outflow_bps`, it succeeds without burning or transferring anything: it
+    /// pauses the protocol and emits `CircuitBreakerTripped`. Callers must check for that event,
+    /// since the transaction does not fail (an error would roll back the pause).
     pub fn redeem(ctx: Context<Redeem>, amount: u64) -> Result<()> {
         processor::redeem(ctx, amount)
     }
 
+    /// Donates vault tokens (e.g., wYLDS) to the vault without minting stake tokens:
+    /// - Raises the assets backing every outstanding share
+    /// - Emits a DonationEvent and adds to the lifetime donation total
+    pub fn donate(ctx: Context<Donate>, amount: u64) -> Result<()> {
+        processor::donate(ctx, amount)
+    }
+
+    /// Replaces the freeze administrators. When `expected_version` is Some it must match
+    /// StakeOperationsConfig.admin_list_version (AdminListVersionMismatch otherwise), which every
+    /// admin-list update increments.
     pub fn update_freeze_administrators(
         ctx: Context<UpdateFreezeAdministrators>,
         new_administrators: Vec<Pubkey>,
+        expected_version: Option<u64>,
     ) -> Result<()> {
-        processor::update_freeze_administrators(ctx, new_administrators)
+        processor::update_freeze_administrators(ctx, new_administrators, expected_version)
     }
 
     pub fn freeze_token_account(ctx: Context<FreezeTokenAccount>) -> Result<()> {
@@ -90,29 +175,234 @@ pub mod vault_stake {
         processor::thaw_token_account(ctx)
     }
 
+    /// Freezes a stake token account and records the reason in its RestrictionRecord PDA.
+    /// Only callable by freeze administrators.
+    pub fn restrict_account(ctx: Context<RestrictAccount>, reason: String) -> Result<()> {
+        processor::restrict_account(ctx, reason)
+    }
+
+    /// Thaws a restricted stake token account, recording who lifted it and when.
+    /// Only callable by freeze administrators.
+    pub fn lift_restriction(ctx: Context<LiftRestriction>) -> Result<()> {
+        processor::lift_restriction(ctx)
+    }
+
+    /// Transfers the stake mint's freeze authority from the signer to the program's
+    /// freeze authority PDA. No-op if the PDA already holds it.
+    /// Only callable by the program upgrade authority.
+    pub fn claim_freeze_authority(ctx: Context<ClaimFreezeAuthority>) -> Result<()> {
+        processor::claim_freeze_authority(ctx)
+    }
+
+    /// Returns whether the stake mint's freeze authority is the program's freeze authority PDA.
+    pub fn validate_freeze_authority(ctx: Context<FreezeAuthorityView>) -> Result<bool> {
+        processor::validate_freeze_authority(ctx)
+    }
+
+    /// Returns the freeze setup status (who holds the stake mint's freeze authority, number of
+    /// freeze administrators, and whether freeze/thaw will work).
+    pub fn validate_freeze_setup(ctx: Context<FreezeAuthorityView>) -> Result<state::FreezeSetup> {
+        processor::validate_freeze_setup(ctx)
+    }
+
+    /// Returns a bitmask of the frozen state of the stake token accounts passed as remaining
+    /// accounts (bit i = remaining_accounts[i] is frozen). At most 64 accounts per call.
+    pub fn freeze_status_many(ctx: Context<StakeMintView>) -> Result<u64> {
+        processor::freeze_status_many(ctx)
+    }
+
+    /// Reports, for up to 16 token accounts passed as remaining accounts, their mint and whether
+    /// the vault authority owns them, flagging owned accounts other than the vault token account
+    /// and the insurance account.
+    pub fn verify_vault_ownership(
+        ctx: Context<VaultOwnershipView>,
+    ) -> Result<state::VaultOwnershipReport> {
+        processor::verify_vault_ownership(ctx)
+    }
+
+    /// Replaces the rewards administrators. When `expected_version` is Some it must match
+    /// StakeOperationsConfig.admin_list_version (AdminListVersionMismatch otherwise), which every
+    /// admin-list update increments.
     pub fn update_rewards_administrators(
         ctx: Context<UpdateRewardsAdministrators>,
         new_administrators: Vec<Pubkey>,
+        expected_version: Option<u64>,
     ) -> Result<()> {
-        processor::update_rewards_administrators(ctx, new_administrators)
+        processor::update_rewards_administrators(ctx, new_administrators, expected_version)
     }
 
-    pub fn publish_rewards(ctx: Context<PublishRewards>, id: u32, amount: u64) -> Result<()> {
+    /// Mints `amount` wYLDS into the vault as rewards. An expired reward record may be passed as
+    /// remaining_accounts[0] to be closed in the same transaction (see reward_record_retention).
+    pub fn publish_rewards<'info>(
+        ctx: Context<'_, '_, 'info, 'info, PublishRewards<'info>>,
+        id: u32,
+        amount: u64,
+    ) -> Result<()> {
         processor::publish_rewards(ctx, id, amount)
     }
 
-    pub fn shares_to_assets(ctx: Context<ConversionView>, shares: u64) -> Result<u64> {
+    /// Publishes rewards by transferring `amount` wYLDS from the reward reserve into the vault
+    /// instead of minting. Fallback for when external minting is disabled.
+    pub fn publish_rewards_from_reserve(
+        ctx: Context<PublishRewardsFromReserve>,
+        id: u32,
+        amount: u64,
+    ) -> Result<()> {
+        processor::publish_rewards_from_reserve(ctx, id, amount)
+    }
+
+    /// Commits the Merkle root of holder balances an off-chain indexer read at `slot`, so
+    /// distributions can prove balances against it. Rewards administrators only; one per slot.
+    pub fn commit_snapshot(
+        ctx: Context<CommitSnapshot>,
+        merkle_root: [u8; 32],
+        total_shares_snapshot: u64,
+        slot: u64,
+    ) -> Result<()> {
+        processor::commit_snapshot(ctx, merkle_root, total_shares_snapshot, slot)
+    }
+
+    /// Returns whether `holder` held `shares` in a committed snapshot, given a Merkle proof of
+    /// leaf = sha256(holder || shares (u64 LE) || slot (u64 LE)). Read-only.
+    pub fn verify_snapshot_claim(
+        ctx: Context<SnapshotView>,
+        holder: Pubkey,
+        shares: u64,
+        proof: Vec<state::ProofNode>,
+    ) -> Result<bool> {
+        processor::verify_snapshot_claim(ctx, holder, shares, proof)
+    }
+
+    /// Publishes a stake-token airdrop root: holders claim extra PRIME with
+    /// claim_stake_reward, up to `total_shares` in all. Rewards administrators only; the
+    /// shares' value at redeem's price counts against the reward caps and admin budgets.
+    pub fn publish_stake_reward(
+        ctx: Context<PublishStakeReward>,
+        merkle_root: [u8; 32],
+        total_shares: u64,
+    ) -> Result<()> {
+        processor::publish_stake_reward(ctx, merkle_root, total_shares)
+    }
+
+    /// Mints `extra_shares` of PRIME to the signer from a published stake reward, given a Merkle
+    /// proof of leaf = sha256(signer || extra_shares (u64 LE)). Each holder claims once.
+    pub fn claim_stake_reward(
+        ctx: Context<ClaimStakeReward>,
+        extra_shares: u64,
+        proof: Vec<state::ProofNode>,
+    ) -> Result<()> {
+        processor::claim_stake_reward(ctx, extra_shares, proof)
+    }
+
+    /// Publishes a zero-amount epoch marker: creates a reward record and emits RewardsPublished
+    /// with amount 0 without minting. publish_rewards itself still requires amount > 0.
+    pub fn publish_epoch_marker(ctx: Context<PublishEpochMarker>, id: u32) -> Result<()> {
+        processor::publish_epoch_marker(ctx, id)
+    }
+
+    /// Converts a PRIME amount to wYLDS at the stored price.
+    /// Returns the amount together with the vault and share mint decimals.
+    pub fn shares_to_assets(
+        ctx: Context<ConversionView>,
+        shares: u64,
+    ) -> Result<state::ConversionResult> {
         processor::shares_to_assets(ctx, shares)
     }
 
-    pub fn assets_to_shares(ctx: Context<ConversionView>, assets: u64) -> Result<u64> {
+    /// Converts a wYLDS amount to PRIME at the stored price.
+    /// Returns the amount together with the vault and share mint decimals.
+    pub fn assets_to_shares(
+        ctx: Context<ConversionView>,
+        assets: u64,
+    ) -> Result<state::ConversionResult> {
         processor::assets_to_shares(ctx, assets)
     }
 
-    pub fn exchange_rate(ctx: Context<ConversionView>) -> Result<u64> {
+    /// Quotes both directions at the stored price in one call: the PRIME for `assets` and the
+    /// wYLDS for `shares`, matching assets_to_shares and shares_to_assets.
+    pub fn convert_both(
+        ctx: Context<ConversionView>,
+        assets: u64,
+        shares: u64,
+    ) -> Result<state::ConvertBothResult> {
+        processor::convert_both(ctx, assets, shares)
+    }
+
+    /// Returns wYLDS per PRIME scaled by 1e9, with the slot of this call.
+    pub fn exchange_rate(ctx: Context<ConversionView>) -> Result<state::ExchangeRateResult> {
         processor::exchange_rate(ctx)
     }
 
+    /// Returns the UI-ready PRIME:wYLDS rate and its inverse (scaled by 1e9, adjusted for
+    /// both mints' decimals) together with the decimals.
+    pub fn display_rate(ctx: Context<ConversionView>) -> Result<state::DisplayRate> {
+        processor::display_rate(ctx)
+    }
+
+    /// Returns exchange_rate as a Pyth-style price feed: price * 10^expo wYLDS per PRIME.
+    pub fn price_feed(ctx: Context<ConversionView>) -> Result<state::PriceFeed> {
+        processor::price_feed(ctx)
+    }
+
+    /// Returns the vault assets to deposit (rounded up) for the holder's stake token balance to
+    /// reach `target_shares` at the stored price; 0 if the balance already meets the target.
+    pub fn assets_for_target_shares(
+        ctx: Context<TargetSharesView>,
+        target_shares: u64,
+    ) -> Result<u64> {
+        processor::assets_for_target_shares(ctx, target_shares)
+    }
+
+    /// Returns a bitmask of health conditions found (HEALTH_* in state.rs); 0 means healthy.
+    pub fn health_check(ctx: Context<HealthCheck>) -> Result<u32> {
+        processor::health_check(ctx)
+    }
+
+    /// Returns the PDA seed scheme version clients should derive addresses with.
+    pub fn seed_scheme(ctx: Context<SeedSchemeView>) -> Result<u8> {
+        processor::seed_scheme(ctx)
+    }
+
+    /// Returns the program's upgrade authority (None if the program is immutable).
+    pub fn get_upgrade_authority(ctx: Context<GetUpgradeAuthority>) -> Result<Option<Pubkey>> {
+        processor::get_upgrade_authority(ctx)
+    }
+
+    /// Returns the stored fields of any owner's legacy unbonding ticket (support view). Legacy
+    /// tickets are always redeemable, so no redeemability or remaining wait is reported.
+    pub fn get_ticket(ctx: Context<TicketView>, owner: Pubkey) -> Result<state::TicketStatus> {
+        processor::get_ticket(ctx, owner)
+    }
+
+    /// Closes the signer's legacy unbonding ticket without redeeming and returns its rent.
+    pub fn cancel_unbond(ctx: Context<CancelUnbond>) -> Result<()> {
+        processor::cancel_unbond(ctx)
+    }
+
+    /// Returns the configured stake mint (PRIME) and vault mint (wYLDS).
+    pub fn get_mints(ctx: Context<StakeConfigView>) -> Result<state::StakeMints> {
+        processor::get_mints(ctx)
+    }
+
+    /// Returns every timing setting front-ends need for redeem, price and reward timelines
+    /// (see state::TimingParams) in one call.
+    pub fn timing_params(ctx: Context<TimingParamsView>) -> Result<state::TimingParams> {
+        processor::timing_params(ctx)
+    }
+
+    /// Returns the vault token account the vault instructions expect, so integrators can fix a
+    /// mis-supplied account that fails with InvalidVaultTokenAccount.
+    pub fn expected_vault_token_account(
+        ctx: Context<VaultTokenAccountConfigView>,
+    ) -> Result<Pubkey> {
+        processor::expected_vault_token_account(ctx)
+    }
+
+    /// Returns exchange_rate split into whole wYLDS per PRIME and the fraction scaled by 1e9.
+    pub fn exchange_rate_parts(ctx: Context<ConversionView>) -> Result<state::ExchangeRateParts> {
+        processor::exchange_rate_parts(ctx)
+    }
+
     // ========== PRICE CONFIG INSTRUCTIONS ==========
 
     /// Creates the StakePriceConfig PDA with Chainlink program references and staleness parameters.
@@ -180,6 +470,13 @@ pub mod vault_stake {
         processor::set_price_for_testing(ctx, price, price_timestamp)
     }
 
+    /// FOR TESTING ONLY — sets StakeConfig.paused alone, as a pre-upgrade pause left it.
+    /// Requires program upgrade authority. Use on localnet only.
+    #[cfg(feature = "testing")]
+    pub fn set_paused_for_testing(ctx: Context<SetPausedForTesting>, paused: bool) -> Result<()> {
+        processor::set_paused_for_testing(ctx, paused)
+    }
+
     /// Creates the StakeRewardConfig PDA with protocol default caps and cooldown.
     /// Must be called once before `publish_rewards` can enforce limits.
     /// Only callable by the program upgrade authority.
@@ -219,4 +516,284 @@ pub mod vault_stake {
     ) -> Result<()> {
         processor::update_max_total_rewards(ctx, new_cap)
     }
+
+    // ========== OPERATIONS CONFIG INSTRUCTIONS ==========
+
+    /// Creates the StakeOperationsConfig PDA with no deployed assets.
+    /// Must be called once after deployment before deposit, redeem or publish_rewards can proceed.
+    /// Only callable by the program upgrade authority.
+    pub fn initialize_stake_operations_config(
+        ctx: Context<InitializeStakeOperationsConfig>,
+    ) -> Result<()> {
+        processor::initialize_stake_operations_config(ctx)
+    }
+
+    /// Sets the amount of vault assets deployed outside the vault token account (e.g. into a
+    /// yield strategy). Total assets are reported as vault balance + deployed assets.
+    /// Only callable by the program upgrade authority.
+    pub fn report_deployed_assets(
+        ctx: Context<UpdateStakeOperationsConfig>,
+        amount: u64,
+    ) -> Result<()> {
+        processor::report_deployed_assets(ctx, amount)
+    }
+
+    /// Sets the share of total assets (BPS) that must remain idle after a redeem; redeems that
+    /// would breach it fail with ReserveFloorBreached. 0 disables the floor.
+    /// Only callable by the program upgrade authority.
+    pub fn update_min_reserve_bps(
+        ctx: Context<UpdateStakeOperationsConfig>,
+        min_reserve_bps: u16,
+    ) -> Result<()> {
+        processor::update_min_reserve_bps(ctx, min_reserve_bps)
+    }
+
+    /// Sets the hook program CPI'd (`on_stake_event`) after every deposit and redeem, and whether
+    /// deposit/redeem fail when it is not passed as the first remaining account. A hook that is
+    /// passed and fails always reverts the deposit/redeem.
+    /// Pass Pubkey::default() to remove the hook. Only callable by the program upgrade authority.
+    pub fn update_hook(
+        ctx: Context<UpdateStakeOperationsConfig>,
+        hook_program: Pubkey,
+        require_hook_account: bool,
+    ) -> Result<()> {
+        processor::update_hook(ctx, hook_program, require_hook_account)
+    }
+
+    /// Enables or disables commit-reveal deposits and sets the minimum slot delay between
+    /// commit_deposit and reveal_deposit. Only callable by the program upgrade authority.
+    pub fn update_commit_reveal(
+        ctx: Context<UpdateStakeOperationsConfig>,
+        enabled: bool,
+        delay_slots: u64,
+    ) -> Result<()> {
+        processor::update_commit_reveal(ctx, enabled, delay_slots)
+    }
+
+    /// Break-glass migration tool: forces deposit and redeem to use `rate_1e9` (wYLDS per PRIME,
+    /// scaled by 1e9) instead of the oracle price. Must be cleared with None to resume normal
+    /// oracle pricing. Only callable by the program upgrade authority.
+    pub fn set_rate_override(
+        ctx: Context<UpdateStakeOperationsConfig>,
+        rate_1e9: Option<u64>,
+    ) -> Result<()> {
+        processor::set_rate_override(ctx, rate_1e9)
+    }
+
+    /// Configures the redeem circuit breaker: a redeem withdrawing more than
+    /// max_single_outflow_bps of total assets fails, or with auto_pause pauses the protocol
+    /// (emitting CircuitBreakerTripped) instead. 0 disables the breaker.
+    /// Only callable by the program upgrade authority.
+    pub fn update_circuit_breaker(
+        ctx: Context<UpdateStakeOperationsConfig>,
+        max_single_outflow_bps: u16,
+        auto_pause: bool,
+    ) -> Result<()> {
+        processor::update_circuit_breaker(ctx, max_single_outflow_bps, auto_pause)
+    }
+
+    /// Sets the minimum seconds between a user's consecutive redeems (partial redeems count).
+    /// 0 disables the cooldown. Only callable by the program upgrade authority.
+    pub fn update_redeem_cooldown(
+        ctx: Context<UpdateStakeOperationsConfig>,
+        redeem_cooldown_seconds: i64,
+    ) -> Result<()> {
+        processor::update_redeem_cooldown(ctx, redeem_cooldown_seconds)
+    }
+
+    /// Sets the vault-mint program id that publish_rewards accepts as mint_program (and that
+    /// must own mint_config). Only callable by the program upgrade authority.
+    pub fn update_allowed_mint_program(
+        ctx: Context<UpdateStakeOperationsConfig>,
+        mint_program: Pubkey,
+    ) -> Result<()> {
+        processor::update_allowed_mint_program(ctx, mint_program)
+    }
+
+    /// Sets (Some) or removes (None) the cap on the stake mint's raw supply; a deposit that would
+    /// exceed it fails with MaxSharesExceeded. Only callable by the program upgrade authority.
+    pub fn update_max_total_shares(
+        ctx: Context<UpdateStakeOperationsConfig>,
+        max_total_shares: Option<u64>,
+    ) -> Result<()> {
+        processor::update_max_total_shares(ctx, max_total_shares)
+    }
+
+    /// Sets how many seconds reward records are kept before publish_rewards may prune them
+    /// (0 keeps them all). Only callable by the program upgrade authority.
+    pub fn update_reward_record_retention(
+        ctx: Context<UpdateStakeOperationsConfig>,
+        reward_record_retention: i64,
+    ) -> Result<()> {
+        processor::update_reward_record_retention(ctx, reward_record_retention)
+    }
+
+    /// Sets the only program zap_deposit may swap through; Pubkey::default() disables zaps.
+    /// Only callable by the program upgrade authority.
+    pub fn update_swap_program(
+        ctx: Context<UpdateStakeOperationsConfig>,
+        swap_program: Pubkey,
+    ) -> Result<()> {
+        processor::update_swap_program(ctx, swap_program)
+    }
+
+    /// Enables or disables publish_rewards' mint CPI into vault-mint; when disabled it fails with
+    /// ExternalMintDisabled. Only callable by the program upgrade authority.
+    pub fn update_external_mint_enabled(
+        ctx: Context<UpdateStakeOperationsConfig>,
+        enabled: bool,
+    ) -> Result<()> {
+        processor::update_external_mint_enabled(ctx, enabled)
+    }
+
+    /// Sets the vault-token account publish_rewards_from_reserve draws from.
+    /// Only callable by the program upgrade authority.
+    pub fn update_reward_reserve(ctx: Context<UpdateRewardReserve>) -> Result<()> {
+        processor::update_reward_reserve(ctx)
+    }
+
+    /// Sets whether update_freeze_administrators / update_rewards_administrators reject an empty
+    /// list (CannotRemoveLastAdministrator). Only callable by the program upgrade authority.
+    pub fn update_nonempty_admin_requirements(
+        ctx: Context<UpdateStakeOperationsConfig>,
+        require_nonempty_freeze_admins: bool,
+        require_nonempty_rewards_admins: bool,
+    ) -> Result<()> {
+        processor::update_nonempty_admin_requirements(
+            ctx,
+            require_nonempty_freeze_admins,
+            require_nonempty_rewards_admins,
+        )
+    }
+
+    /// Sets the stake mint supply publish_rewards requires (InsufficientSupplyForRewards below
+    /// it); 0 disables the check. Only callable by the program upgrade authority.
+    pub fn update_min_supply_for_rewards(
+        ctx: Context<UpdateStakeOperationsConfig>,
+        min_supply_for_rewards: u64,
+    ) -> Result<()> {
+        processor::update_min_supply_for_rewards(ctx, min_supply_for_rewards)
+    }
+
+    /// Sets how long a pause may block redeem (at most MAX_PAUSE_DURATION, 30 days); after that
+    /// redeem ignores the pause. Only callable by the program upgrade authority.
+    pub fn update_max_pause_duration(
+        ctx: Context<UpdateStakeOperationsConfig>,
+        max_pause_duration_seconds: i64,
+    ) -> Result<()> {
+        processor::update_max_pause_duration(ctx, max_pause_duration_seconds)
+    }
+
+    /// Sets the share of each deposit (BPS) routed to the insurance token account instead of
+    /// the vault. Only callable by the program upgrade authority.
+    pub fn update_insurance_config(
+        ctx: Context<UpdateInsuranceConfig>,
+        insurance_bps: u16,
+    ) -> Result<()> {
+        processor::update_insurance_config(ctx, insurance_bps)
+    }
+
+    /// Moves insurance funds into the vault to cover a shortfall.
+    /// Only callable by the program upgrade authority.
+    pub fn draw_insurance(ctx: Context<DrawInsurance>, amount: u64) -> Result<()> {
+        processor::draw_insurance(ctx, amount)
+    }
+
+    /// Returns the vault's total assets (vault token balance + deployed assets).
+    pub fn total_assets(ctx: Context<VaultAssetsView>) -> Result<u64> {
+        processor::total_assets(ctx)
+    }
+
+    /// Returns the assets backing `user_shares` (pro-rata share of total assets) after a
+    /// hypothetical `reward_amount` is published. Read-only.
+    pub fn project_value(
+        ctx: Context<VaultAssetsView>,
+        user_shares: u64,
+        reward_amount: u64,
+    ) -> Result<u64> {
+        processor::project_value(ctx, user_shares, reward_amount)
+    }
+
+    /// Returns the idle vault token balance that redemptions can draw on right now.
+    /// Deployed assets are not included.
+    pub fn available_liquidity(ctx: Context<VaultAssetsView>) -> Result<u64> {
+        processor::available_liquidity(ctx)
+    }
+
+    /// Returns the value deposit and redeem rounding has left in the vault, in vault-token base
+    /// units scaled by 1e18 (ROUNDING_DUST_SCALE).
+    pub fn rounding_dust(ctx: Context<VaultAssetsView>) -> Result<u128> {
+        processor::rounding_dust(ctx)
+    }
+
+    /// Returns the rolling sha256 digest of every event emitted so far (events_digest), for
+    /// indexers to check they processed the complete event stream.
+    pub fn events_digest(ctx: Context<VaultAssetsView>) -> Result<[u8; 32]> {
+        processor::events_digest(ctx)
+    }
+
+    /// Returns the shares redeem would burn to pay out exactly `assets_out`, rounded up in the
+    /// vault's favor (ERC4626 previewWithdraw).
+    pub fn shares_for_exact_assets(ctx: Context<VaultAssetsView>, assets_out: u64) -> Result<u64> {
+        processor::shares_for_exact_assets(ctx, assets_out)
+    }
+
+    /// Returns real vault assets over the value owed to shareholders at redeem's price, in BPS
+    /// (10_000 = fully collateralized, u64::MAX when no shares are outstanding).
+    pub fn collateralization(ctx: Context<VaultAssetsView>) -> Result<u64> {
+        processor::collateralization(ctx)
+    }
+
+    /// Dry-runs depositing `amount` and immediately redeeming the minted shares; returns the
+    /// round-trip loss (insurance cut plus rounding) in vault-token base units.
+    pub fn simulate_cycle(ctx: Context<VaultAssetsView>, amount: u64) -> Result<u64> {
+        processor::simulate_cycle(ctx, amount)
+    }
+
+    // ========== REWARD POLICY CONFIG INSTRUCTIONS ==========
+
+    /// Creates the StakeRewardPolicyConfig PDA with no per-admin budgets.
+    /// Must be called once after deployment before publish_rewards can proceed.
+    /// Only callable by the program upgrade authority.
+    pub fn initialize_stake_reward_policy_config(
+        ctx: Context<InitializeStakeRewardPolicyConfig>,
+    ) -> Result<()> {
+        processor::initialize_stake_reward_policy_config(ctx)
+    }
+
+    /// Sets the remaining publication budget of a rewards administrator. Each publish_rewards
+    /// call by that administrator is deducted from it; administrators without a budget are
+    /// limited only by the global reward caps. Only callable by the program upgrade authority.
+    pub fn set_reward_budget(
+        ctx: Context<UpdateStakeRewardPolicyConfig>,
+        admin: Pubkey,
+        budget: u64,
+    ) -> Result<()> {
+        processor::set_reward_budget(ctx, admin, budget)
+    }
+
+    /// Temporarily disables (or re-enables) a rewards administrator. A disabled administrator
+    /// stays in rewards_administrators but publish_rewards rejects it.
+    /// Only callable by the program upgrade authority.
+    pub fn set_rewards_admin_enabled(
+        ctx: Context<UpdateStakeRewardPolicyConfig>,
+        admin: Pubkey,
+        enabled: bool,
+    ) -> Result<()> {
+        processor::set_rewards_admin_enabled(ctx, admin, enabled)
+    }
+
+    /// Requires strictly increasing reward ids across publish_rewards and publish_epoch_marker
+    /// (RewardIdNotMonotonic otherwise). Only callable by the program upgrade authority.
+    pub fn set_reward_id_enforcement(
+        ctx: Context<UpdateStakeRewardPolicyConfig>,
+        enforce_monotonic_ids: bool,
+    ) -> Result<()> {
+        processor::set_reward_id_enforcement(ctx, enforce_monotonic_ids)
+    }
+
+    /// Returns the number of reward records created by publish_rewards and publish_epoch_marker.
+    pub fn reward_record_count(ctx: Context<RewardPolicyView>) -> Result<u64> {
+        processor::reward_record_count(ctx)
+    }
 }
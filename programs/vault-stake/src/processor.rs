@@ -1,12 +1,26 @@
 use crate::account_structs::*;
 use crate::error::*;
 use crate::events::*;
-use crate::guard::validate_program_update_authority;
-use crate::state::{StakeRewardConfig, MAX_ADMINISTRATORS};
+use crate::guard::{read_program_upgrade_authority, validate_program_update_authority};
+use crate::state::{
+    ConversionResult, ConvertBothResult, DisplayRate, ExchangeRateParts, ExchangeRateResult,
+    FreezeAuthorityStatus, FreezeSetup, PriceFeed, ProofNode, RewardPublicationRecord, StakeConfig,
+    StakeHookKind, StakeHookPayload, StakeMints, StakeOperationsConfig, StakePriceConfig,
+    StakeRewardConfig, StakeRewardPolicyConfig, TicketStatus, TimingParams, VaultOwnershipEntry,
+    VaultOwnershipReport, VestingAccount, EXCHANGE_RATE_SCALE, HEALTH_PAUSED,
+    HEALTH_PRICE_NOT_INITIALIZED, HEALTH_PRICE_STALE, HEALTH_RATE_OVERRIDE_ACTIVE,
+    HEALTH_SOLVENCY_TOLERANCE_BPS, HEALTH_UNDERCOLLATERALIZED, HEALTH_VAULT_NOT_PROGRAM_OWNED,
+    MAX_ADMINISTRATORS, MAX_FREEZE_STATUS_ACCOUNTS, MAX_PAUSE_DURATION, MAX_RESTRICTION_REASON_LEN,
+    MAX_VAULT_OWNERSHIP_ACCOUNTS, PAUSE_ALL_OPERATIONS, PAUSE_DEPOSIT, PAUSE_FREEZE,
+    PAUSE_PUBLISH_REWARDS, PAUSE_REDEEM, PRICE_FEED_CONFIDENCE, ROUNDING_DUST_SCALE,
+    SEED_SCHEME_VERSION, STAKE_HOOK_DISCRIMINATOR,
+};
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
 use anchor_lang::solana_program::program::{get_return_data, invoke};
 use anchor_spl::token::spl_token::instruction::AuthorityType;
-use anchor_spl::token::{self, Burn, MintTo, Transfer};
+use anchor_spl::token::{self, Burn, MintTo, TokenAccount, Transfer};
 use chainlink_data_streams_report::feed_id::ID as FeedId;
 use chainlink_data_streams_report::report::v7::ReportDataV7;
 use chainlink_solana_data_streams::VerifierInstructions;
@@ -16,20 +30,24 @@ pub fn initialize(
     ctx: Context<Initialize>,
     freeze_administrators: Vec<Pubkey>,
     rewards_administrators: Vec<Pubkey>,
+    require_empty_vault_on_init: Option<bool>,
 ) -> Result<()> {
     validate_program_update_authority(&ctx.accounts.program_data, &ctx.accounts.signer)?;
-    require!(
-        freeze_administrators.len() <= MAX_ADMINISTRATORS,
-        CustomErrorCode::TooManyAdministrators
-    );
-    require!(
-        rewards_administrators.len() <= MAX_ADMINISTRATORS,
-        CustomErrorCode::TooManyAdministrators
-    );
+    validate_administrators(&freeze_administrators)?;
+    validate_administrators(&rewards_administrators)?;
     require!(
         ctx.accounts.vault_token_mint.key() != ctx.accounts.mint.key(),
         CustomErrorCode::VaultAndMintCannotBeSame
     );
+    // Tokens already sitting in the vault are backed by no shares and would
+    // silently skew the starting exchange rate. Admins who pre-fund on purpose
+    // must opt out explicitly.
+    if require_empty_vault_on_init.unwrap_or(true) {
+        require!(
+            ctx.accounts.vault_token_account.amount == 0,
+            CustomErrorCode::VaultNotEmpty
+        );
+    }
 
     let config = &mut ctx.accounts.stake_config;
     config.vault = ctx.accounts.vault_token_mint.key();
@@ -78,83 +96,213 @@ pub fn pause(ctx: Context<Pause>, pause: bool) -> Result<()> {
         CustomErrorCode::UnauthorizedFreezeAdministrator
     );
 
+    let was_paused = config.paused;
     let config = &mut ctx.accounts.stake_config;
     config.paused = pause;
+    let operations_config = &mut ctx.accounts.stake_operations_config;
+    if !pause {
+        operations_config.paused_at = 0;
+    } else if !was_paused {
+        operations_config.paused_at = Clock::get()?.unix_timestamp;
+    }
 
     msg!("Protocol paused: {}", pause);
 
     Ok(())
 }
 
+/// Replaces the per-operation pause mask (PAUSE_* bits). Only callable by freeze administrators.
+/// Setting PAUSE_REDEEM records redeem_paused_at; clearing it resets it to 0.
+pub fn set_paused_operations(ctx: Context<Pause>, mask: u8) -> Result<()> {
+    let signer = ctx.accounts.signer.key();
+    require!(
+        ctx.accounts
+            .stake_config
+            .freeze_administrators
+            .contains(&signer),
+        CustomErrorCode::UnauthorizedFreezeAdministrator
+    );
+    require!(
+        mask & !PAUSE_ALL_OPERATIONS == 0,
+        CustomErrorCode::InvalidPauseMask
+    );
+
+    let config = &mut ctx.accounts.stake_operations_config;
+    let old_mask = config.paused_operations;
+    if mask & PAUSE_REDEEM == 0 {
+        config.redeem_paused_at = 0;
+    } else if old_mask & PAUSE_REDEEM == 0 {
+        config.redeem_paused_at = Clock::get()?.unix_timestamp;
+    }
+    config.paused_operations = mask;
+
+    emit_digested(
+        &mut ctx.accounts.stake_operations_config,
+        PauseMaskUpdated {
+            admin: signer,
+            old_mask,
+            new_mask: mask,
+            stake_config: ctx.accounts.stake_config.key(),
+        },
+    );
+
+    msg!(
+        "paused_operations updated: {:#07b} -> {:#07b}",
+        old_mask,
+        mask
+    );
+    Ok(())
+}
+
 pub fn deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
+    execute_deposit(
+        ctx.accounts,
+        ctx.bumps.mint_authority,
+        ctx.remaining_accounts,
+        amount,
+    )
+}
+
+/// Shared body of deposit and reveal_deposit: prices the deposit at the current slot, moves the
+/// vault tokens in, mints the shares and runs the hook.
+fn execute_deposit<'info>(
+    accounts: &mut Deposit,
+    mint_authority_bump: u8,
+    remaining_accounts: &[AccountInfo<'info>],
+    amount: u64,
+) -> Result<()> {
     require!(amount > 0, CustomErrorCode::InvalidAmount);
     require!(
-        !ctx.accounts.stake_config.paused,
+        !accounts.stake_config.paused,
         CustomErrorCode::ProtocolPaused
     );
+    require_operation_active(&accounts.stake_operations_config, PAUSE_DEPOSIT)?;
 
-    let total_assets = ctx.accounts.vault_token_account.amount;
-    let total_shares = ctx.accounts.mint.supply;
+    let total_assets = total_vault_assets(
+        accounts.vault_token_account.amount,
+        &accounts.stake_operations_config,
+    )?;
+    let total_shares = accounts.mint.supply;
 
     msg!("Current total_assets: {}", total_assets);
     msg!("Current total_shares: {}", total_shares);
     msg!("Deposit amount: {}", amount);
+    // Reject amounts the vault balance can never hold before they reach the pricing math, so
+    // they surface as DepositAmountTooLarge rather than a generic Overflow.
+    require!(
+        total_assets.checked_add(amount).is_some(),
+        CustomErrorCode::DepositAmountTooLarge
+    );
 
     // Chainlink price-based share calculation.
     // price convention: price = (wYLDS per 1 PRIME) * price_scale
     // Formula: shares = deposit_wYLDS * price_scale / price
-    let price_config = &ctx.accounts.stake_price_config;
-    let current_time = Clock::get()?.unix_timestamp;
-    require!(
-        price_config.price_timestamp > 0,
-        CustomErrorCode::PriceNotInitialized
-    );
-    // Staleness is measured from price_timestamp, which (after a real verify_price) is the report’s
-    // observations_timestamp — the end of the Chainlink-vouched applicability window, not the
-    // on-chain time when verify_price was executed.
-    require!(
-        current_time
-            .checked_sub(price_config.price_timestamp)
-            .ok_or(CustomErrorCode::Overflow)?
-            <= price_config.price_max_staleness,
-        CustomErrorCode::PriceTooStale
-    );
-    require!(price_config.price > 0, CustomErrorCode::PriceNotInitialized);
+    // The first deposit (total_shares == 0) uses the same formula: there is no virtual offset, so
+    // its realized rate is the displayed exchange rate up to flooring.
+    // An active rate override replaces the oracle price (and its staleness checks).
+    let (price, price_scale) = match accounts.stake_operations_config.rate_override_1e9 {
+        Some(rate) => (rate as i128, EXCHANGE_RATE_SCALE),
+        None => {
+            let price_config = &accounts.stake_price_config;
+            let current_time = Clock::get()?.unix_timestamp;
+            require!(
+                price_config.price_timestamp > 0,
+                CustomErrorCode::PriceNotInitialized
+            );
+            // Staleness is measured from price_timestamp, which (after a real verify_price) is
+            // the report’s observations_timestamp — the end of the Chainlink-vouched
+            // applicability window, not the on-chain time when verify_price was executed.
+            require!(
+                current_time
+                    .checked_sub(price_config.price_timestamp)
+                    .ok_or(CustomErrorCode::Overflow)?
+                    <= price_config.price_max_staleness,
+                CustomErrorCode::PriceTooStale
+            );
+            require!(price_config.price > 0, CustomErrorCode::PriceNotInitialized);
+            (price_config.price, price_config.price_scale)
+        }
+    };
 
-    let shares_to_mint = (amount as u128)
-        .checked_mul(price_config.price_scale as u128)
-        .ok_or(CustomErrorCode::Overflow)?
-        .checked_div(price_config.price as u128)
+    // insurance_bps of the deposit goes to the insurance fund; only the rest enters the vault
+    // and is priced into shares.
+    let insurance_amount = insurance_share(amount, &accounts.stake_operations_config)?;
+    let vault_amount = amount
+        .checked_sub(insurance_amount)
+        .ok_or(CustomErrorCode::Overflow)?;
+    if insurance_amount > 0 {
+        msg!("Insurance amount: {}", insurance_amount);
+    }
+
+    let deposit_numerator = (vault_amount as u128)
+        .checked_mul(price_scale as u128)
+        .ok_or(CustomErrorCode::Overflow)?;
+    let shares_to_mint = deposit_numerator
+        .checked_div(price as u128)
         .ok_or(CustomErrorCode::DivisionByZero)?;
     msg!("Shares to mint calculated: {}", shares_to_mint);
+    // The unminted share fraction (remainder / price) is worth remainder / price_scale vault tokens.
+    accrue_rounding_dust(
+        &mut accounts.stake_operations_config,
+        deposit_numerator % price as u128,
+        price_scale,
+    )?;
 
     // Require that user receives at least some shares
     require!(shares_to_mint > 0, CustomErrorCode::DepositTooSmall);
 
+    // Only the deposit amount can push the share supply past u64::MAX here.
     let shares_to_mint_u64: u64 = shares_to_mint
         .try_into()
-        .map_err(|_| CustomErrorCode::Overflow)?;
+        .map_err(|_| CustomErrorCode::DepositAmountTooLarge)?;
+    let result_total_shares = total_shares
+        .checked_add(shares_to_mint_u64)
+        .ok_or(CustomErrorCode::DepositAmountTooLarge)?;
+    if let Some(max_total_shares) = accounts.stake_operations_config.max_total_shares {
+        require!(
+            result_total_shares <= max_total_shares,
+            CustomErrorCode::MaxSharesExceeded
+        );
+    }
 
     let cpi_accounts = Transfer {
-        from: ctx.accounts.user_vault_token_account.to_account_info(),
-        to: ctx.accounts.vault_token_account.to_account_info(),
-        authority: ctx.accounts.signer.to_account_info(),
+        from: accounts.user_vault_token_account.to_account_info(),
+        to: accounts.vault_token_account.to_account_info(),
+        authority: accounts.signer.to_account_info(),
     };
     token::transfer(
-        CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts),
-        amount,
+        CpiContext::new(accounts.token_program.to_account_info(), cpi_accounts),
+        vault_amount,
     )?;
 
-    let seeds: &[&[u8]] = &[b"mint_authority", &[ctx.bumps.mint_authority]];
+    if accounts.stake_operations_config.insurance_bps > 0 {
+        let insurance_token_account = accounts
+            .insurance_token_account
+            .as_ref()
+            .ok_or(CustomErrorCode::InsuranceTokenAccountRequired)?;
+        if insurance_amount > 0 {
+            let cpi_accounts = Transfer {
+                from: accounts.user_vault_token_account.to_account_info(),
+                to: insurance_token_account.to_account_info(),
+                authority: accounts.signer.to_account_info(),
+            };
+            token::transfer(
+                CpiContext::new(accounts.token_program.to_account_info(), cpi_accounts),
+                insurance_amount,
+            )?;
+        }
+    }
+
+    let seeds: &[&[u8]] = &[b"mint_authority", &[mint_authority_bump]];
     let signer = &[&seeds[..]];
     let cpi_accounts = MintTo {
-        mint: ctx.accounts.mint.to_account_info(),
-        to: ctx.accounts.user_mint_token_account.to_account_info(),
-        authority: ctx.accounts.mint_authority.to_account_info(),
+        mint: accounts.mint.to_account_info(),
+        to: accounts.user_mint_token_account.to_account_info(),
+        authority: accounts.mint_authority.to_account_info(),
     };
     token::mint_to(
         CpiContext::new_with_signer(
-            ctx.accounts.token_program.to_account_info(),
+            accounts.token_program.to_account_info(),
             cpi_accounts,
             signer,
         ),
@@ -162,62 +310,531 @@ pub fn deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
     )?;
 
     let result_total_assets = total_assets
-        .checked_add(amount)
-        .ok_or(CustomErrorCode::Overflow)?;
-    let result_total_shares = total_shares
-        .checked_add(shares_to_mint_u64)
+        .checked_add(vault_amount)
         .ok_or(CustomErrorCode::Overflow)?;
-    let totals_last_update_slot = Clock::get()?.slot;
+    let totals_last_update_slot = record_totals_update(&mut accounts.stake_operations_config)?;
+
+    // Invariant: idle balance plus deployed assets must equal the reported total after the
+    // deposit, so DepositEvent never advertises assets the vault does not hold or track.
+    accounts.vault_token_account.reload()?;
+    let idle_after = accounts.vault_token_account.amount;
+    require!(
+        total_vault_assets(idle_after, &accounts.stake_operations_config)? == result_total_assets,
+        CustomErrorCode::DepositAccountingMismatch
+    );
+    let idle_liquidity_bps = bps_of(idle_after, result_total_assets)?;
 
     msg!("Emitting DepositEvent");
-    emit!(DepositEvent {
-        user: ctx.accounts.signer.key(),
-        deposit_amount: amount,
-        minted_amount: shares_to_mint_u64,
-        mint: ctx.accounts.mint.key(),
-        mint_supply: ctx.accounts.mint.supply,
-        vault: ctx.accounts.vault_token_account.key(),
-        vault_balance: ctx.accounts.vault_token_account.amount,
-        total_assets: result_total_assets,
-        total_shares: result_total_shares,
-        totals_last_update_slot,
-    });
+    emit_digested(
+        &mut accounts.stake_operations_config,
+        DepositEvent {
+            user: accounts.user_vault_token_account.owner,
+            deposit_amount: amount,
+            minted_amount: shares_to_mint_u64,
+            mint: accounts.mint.key(),
+            mint_supply: accounts.mint.supply,
+            vault: accounts.vault_token_account.key(),
+            vault_balance: accounts.vault_token_account.amount,
+            total_assets: result_total_assets,
+            total_shares: result_total_shares,
+            totals_last_update_slot,
+            idle_liquidity_bps,
+            insurance_amount,
+            first_deposit: total_shares == 0,
+        },
+    );
     msg!("Emitted DepositEvent");
 
+    invoke_hook(
+        &accounts.stake_operations_config,
+        remaining_accounts,
+        StakeHookPayload {
+            kind: StakeHookKind::Deposit,
+            user: accounts.user_vault_token_account.owner,
+            assets: vault_amount,
+            shares: shares_to_mint_u64,
+            total_assets: result_total_assets,
+            total_shares: result_total_shares,
+        },
+    )
+}
+
+/// Deposit that first creates the signer's stake token ATA when it is missing (init_if_needed),
+/// then runs the regular deposit path.
+pub fn deposit_with_ata(ctx: Context<DepositWithAta>, amount: u64) -> Result<()> {
+    let accounts = &ctx.accounts;
+    let mut deposit = Deposit {
+        stake_config: accounts.stake_config.clone(),
+        stake_vault_token_account_config: accounts.stake_vault_token_account_config.clone(),
+        vault_token_account: accounts.vault_token_account.clone(),
+        vault_authority: accounts.vault_authority.clone(),
+        mint: accounts.mint.clone(),
+        vault_mint: accounts.vault_mint.clone(),
+        mint_authority: accounts.mint_authority.clone(),
+        signer: accounts.signer.clone(),
+        user_vault_token_account: accounts.user_vault_token_account.clone(),
+        user_mint_token_account: accounts.user_mint_token_account.clone(),
+        stake_price_config: accounts.stake_price_config.clone(),
+        stake_operations_config: accounts.stake_operations_config.clone(),
+        insurance_token_account: accounts.insurance_token_account.clone(),
+        token_program: accounts.token_program.clone(),
+    };
+    execute_deposit(
+        &mut deposit,
+        ctx.bumps.mint_authority,
+        ctx.remaining_accounts,
+        amount,
+    )?;
+
+    // execute_deposit updated the cloned account; only ctx.accounts is written back on exit.
+    copy_deposit_updates(
+        &mut ctx.accounts.stake_operations_config,
+        &deposit.stake_operations_config,
+    );
+    Ok(())
+}
+
+/// Deposit executed by a relayer for the user: the relayer signs (and pays the fees) as the
+/// delegate the user approved on their vault token account, so the transfer in spends that
+/// approval, and the shares are minted to the user's stake token account.
+pub fn deposit_with_relayer(ctx: Context<DepositWithRelayer>, amount: u64) -> Result<()> {
+    require!(
+        ctx.accounts.user_vault_token_account.delegated_amount >= amount,
+        CustomErrorCode::InsufficientDelegatedAmount
+    );
+
+    let accounts = &ctx.accounts;
+    let mut deposit = Deposit {
+        stake_config: accounts.stake_config.clone(),
+        stake_vault_token_account_config: accounts.stake_vault_token_account_config.clone(),
+        vault_token_account: accounts.vault_token_account.clone(),
+        vault_authority: accounts.vault_authority.clone(),
+        mint: accounts.mint.clone(),
+        vault_mint: accounts.vault_mint.clone(),
+        mint_authority: accounts.mint_authority.clone(),
+        signer: accounts.relayer.clone(),
+        user_vault_token_account: accounts.user_vault_token_account.clone(),
+        user_mint_token_account: accounts.user_mint_token_account.clone(),
+        stake_price_config: accounts.stake_price_config.clone(),
+        stake_operations_config: accounts.stake_operations_config.clone(),
+        insurance_token_account: accounts.insurance_token_account.clone(),
+        token_program: accounts.token_program.clone(),
+    };
+    execute_deposit(
+        &mut deposit,
+        ctx.bumps.mint_authority,
+        ctx.remaining_accounts,
+        amount,
+    )?;
+
+    // execute_deposit updated the cloned account; only ctx.accounts is written back on exit.
+    copy_deposit_updates(
+        &mut ctx.accounts.stake_operations_config,
+        &deposit.stake_operations_config,
+    );
+
+    emit_digested(
+        &mut ctx.accounts.stake_operations_config,
+        RelayedDeposit {
+            user: ctx.accounts.user.key(),
+            relayer: ctx.accounts.relayer.key(),
+            amount,
+            stake_config: ctx.accounts.stake_config.key(),
+        },
+    );
+
+    msg!(
+        "deposit_with_relayer: {} deposited for {} by {}",
+        amount,
+        ctx.accounts.user.key(),
+        ctx.accounts.relayer.key()
+    );
+    Ok(())
+}
+
+/// Swaps an input token for the vault token through the allowlisted swap program, then deposits
+/// what the swap delivered. The remaining accounts and `swap_data` form the swap instruction; the
+/// accounts keep the signer privileges of this transaction, so the signer can authorize the swap.
+/// The swap may spend at most `amount_in` of user_input_token_account and must deliver at least
+/// `min_vault_out` to user_vault_token_account. The deposit hook is not invoked.
+pub fn zap_deposit<'info>(
+    ctx: Context<'_, '_, 'info, 'info, ZapDeposit<'info>>,
+    amount_in: u64,
+    min_vault_out: u64,
+    swap_data: Vec<u8>,
+) -> Result<()> {
+    require!(amount_in > 0, CustomErrorCode::InvalidAmount);
+    let input_before = ctx.accounts.user_input_token_account.amount;
+    let vault_before = ctx.accounts.user_vault_token_account.amount;
+
+    let instruction = Instruction {
+        program_id: ctx.accounts.swap_program.key(),
+        accounts: ctx
+            .remaining_accounts
+            .iter()
+            .map(|account| AccountMeta {
+                pubkey: account.key(),
+                is_signer: account.is_signer,
+                is_writable: account.is_writable,
+            })
+            .collect(),
+        data: swap_data,
+    };
+    msg!("Invoking swap program {}", ctx.accounts.swap_program.key());
+    let mut account_infos = ctx.remaining_accounts.to_vec();
+    account_infos.push(ctx.accounts.swap_program.clone());
+    invoke(&instruction, &account_infos)?;
+
+    // The swap may have touched any token account it was given; price the deposit from fresh state.
+    let accounts = &mut *ctx.accounts;
+    accounts.user_input_token_account.reload()?;
+    accounts.user_vault_token_account.reload()?;
+    accounts.vault_token_account.reload()?;
+    accounts.mint.reload()?;
+
+    let input_spent = input_before.saturating_sub(accounts.user_input_token_account.amount);
+    require!(input_spent <= amount_in, CustomErrorCode::ZapInputExceeded);
+    let vault_received = accounts
+        .user_vault_token_account
+        .amount
+        .saturating_sub(vault_before);
+    require!(
+        vault_received >= min_vault_out,
+        CustomErrorCode::ZapSlippageExceeded
+    );
+
+    let mut deposit = Deposit {
+        stake_config: accounts.stake_config.clone(),
+        stake_vault_token_account_config: accounts.stake_vault_token_account_config.clone(),
+        vault_token_account: accounts.vault_token_account.clone(),
+        vault_authority: accounts.vault_authority.clone(),
+        mint: accounts.mint.clone(),
+        vault_mint: accounts.vault_mint.clone(),
+        mint_authority: accounts.mint_authority.clone(),
+        signer: accounts.signer.clone(),
+        user_vault_token_account: accounts.user_vault_token_account.clone(),
+        user_mint_token_account: accounts.user_mint_token_account.clone(),
+        stake_price_config: accounts.stake_price_config.clone(),
+        stake_operations_config: accounts.stake_operations_config.clone(),
+        insurance_token_account: accounts.insurance_token_account.clone(),
+        token_program: accounts.token_program.clone(),
+    };
+    // The remaining accounts belong to the swap, so no hook program is passed to the deposit.
+    execute_deposit(&mut deposit, ctx.bumps.mint_authority, &[], vault_received)?;
+
+    // execute_deposit updated the cloned account; only ctx.accounts is written back on exit.
+    copy_deposit_updates(
+        &mut ctx.accounts.stake_operations_config,
+        &deposit.stake_operations_config,
+    );
+
+    emit_digested(
+        &mut ctx.accounts.stake_operations_config,
+        ZapDeposited {
+            user: ctx.accounts.signer.key(),
+            swap_program: ctx.accounts.swap_program.key(),
+            input_mint: ctx.accounts.user_input_token_account.mint,
+            input_spent,
+            vault_received,
+            stake_config: ctx.accounts.stake_config.key(),
+        },
+    );
+
+    msg!(
+        "zap_deposit: {} input spent, {} vault tokens deposited",
+        input_spent,
+        vault_received
+    );
+    Ok(())
+}
+
+/// Deposit whose shares are minted into the signer's vesting escrow and released linearly over
+/// vesting_duration seconds by claim_vested. Starts a new schedule, so the previous one (if any)
+/// must be fully claimed.
+pub fn deposit_vesting(
+    ctx: Context<DepositVesting>,
+    amount: u64,
+    vesting_duration: i64,
+) -> Result<()> {
+    require!(
+        vesting_duration > 0,
+        CustomErrorCode::InvalidVestingDuration
+    );
+    let vesting_account = &ctx.accounts.vesting_account;
+    require!(
+        vesting_account.claimed_shares == vesting_account.total_shares,
+        CustomErrorCode::VestingScheduleActive
+    );
+    let escrow_before = ctx.accounts.vesting_escrow.amount;
+
+    let accounts = &ctx.accounts;
+    let mut deposit = Deposit {
+        stake_config: accounts.stake_config.clone(),
+        stake_vault_token_account_config: accounts.stake_vault_token_account_config.clone(),
+        vault_token_account: accounts.vault_token_account.clone(),
+        vault_authority: accounts.vault_authority.clone(),
+        mint: accounts.mint.clone(),
+        vault_mint: accounts.vault_mint.clone(),
+        mint_authority: accounts.mint_authority.clone(),
+        signer: accounts.signer.clone(),
+        user_vault_token_account: accounts.user_vault_token_account.clone(),
+        user_mint_token_account: accounts.vesting_escrow.clone(),
+        stake_price_config: accounts.stake_price_config.clone(),
+        stake_operations_config: accounts.stake_operations_config.clone(),
+        insurance_token_account: accounts.insurance_token_account.clone(),
+        token_program: accounts.token_program.clone(),
+    };
+    execute_deposit(
+        &mut deposit,
+        ctx.bumps.mint_authority,
+        ctx.remaining_accounts,
+        amount,
+    )?;
+
+    // execute_deposit updated the cloned account; only ctx.accounts is written back on exit.
+    copy_deposit_updates(
+        &mut ctx.accounts.stake_operations_config,
+        &deposit.stake_operations_config,
+    );
+    deposit.user_mint_token_account.reload()?;
+    let minted_shares = deposit
+        .user_mint_token_account
+        .amount
+        .checked_sub(escrow_before)
+        .ok_or(CustomErrorCode::Overflow)?;
+
+    let start_ts = Clock::get()?.unix_timestamp;
+    let vesting_account = &mut ctx.accounts.vesting_account;
+    vesting_account.user = ctx.accounts.signer.key();
+    vesting_account.total_shares = minted_shares;
+    vesting_account.claimed_shares = 0;
+    vesting_account.start_ts = start_ts;
+    vesting_account.duration_seconds = vesting_duration;
+    vesting_account.bump = ctx.bumps.vesting_account;
+
+    emit_digested(
+        &mut ctx.accounts.stake_operations_config,
+        VestingDeposited {
+            user: ctx.accounts.signer.key(),
+            deposit_amount: amount,
+            vested_shares: minted_shares,
+            start_ts,
+            duration_seconds: vesting_duration,
+            stake_config: ctx.accounts.stake_config.key(),
+        },
+    );
+    msg!(
+        "vesting deposit: {} shares over {} seconds",
+        minted_shares,
+        vesting_duration
+    );
+    Ok(())
+}
+
+/// Transfers the vested but unclaimed shares of the signer's vesting schedule from the escrow to
+/// their stake token account.
+pub fn claim_vested(ctx: Context<ClaimVested>) -> Result<()> {
+    let vesting_account = &ctx.accounts.vesting_account;
+    let vested = vested_shares(vesting_account, Clock::get()?.unix_timestamp)?;
+    let claimable = vested
+        .checked_sub(vesting_account.claimed_shares)
+        .ok_or(CustomErrorCode::Overflow)?;
+    require!(claimable > 0, CustomErrorCode::NothingVested);
+
+    let stake_config_key = ctx.accounts.stake_config.key();
+    let user_key = ctx.accounts.signer.key();
+    let seeds: &[&[u8]] = &[
+        b"vesting",
+        stake_config_key.as_ref(),
+        user_key.as_ref(),
+        &[vesting_account.bump],
+    ];
+    let signer = &[seeds];
+    token::transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.vesting_escrow.to_account_info(),
+                to: ctx.accounts.user_mint_token_account.to_account_info(),
+                authority: ctx.accounts.vesting_account.to_account_info(),
+            },
+            signer,
+        ),
+        claimable,
+    )?;
+
+    let vesting_account = &mut ctx.accounts.vesting_account;
+    vesting_account.claimed_shares = vested;
+
+    emit_digested(
+        &mut ctx.accounts.stake_operations_config,
+        VestedClaimed {
+            user: user_key,
+            amount: claimable,
+            claimed_shares: vested,
+            total_shares: vesting_account.total_shares,
+            stake_config: stake_config_key,
+        },
+    );
+    msg!(
+        "claimed {} vested shares ({} of {})",
+        claimable,
+        vested,
+        vesting_account.total_shares
+    );
+    Ok(())
+}
+
+/// Records a hashed deposit intent: commitment = sha256(user || amount_le || salt).
+/// Committing again replaces the pending commitment and restarts the delay.
+pub fn commit_deposit(ctx: Context<CommitDeposit>, commitment: [u8; 32]) -> Result<()> {
+    require!(
+        !ctx.accounts.stake_config.paused,
+        CustomErrorCode::ProtocolPaused
+    );
+    let operations_config = &ctx.accounts.stake_operations_config;
+    require!(
+        operations_config.commit_reveal_enabled,
+        CustomErrorCode::CommitRevealDisabled
+    );
+
+    let committed_slot = Clock::get()?.slot;
+    let reveal_after_slot = committed_slot
+        .checked_add(operations_config.commit_reveal_delay_slots)
+        .ok_or(CustomErrorCode::Overflow)?;
+
+    let deposit_commitment = &mut ctx.accounts.deposit_commitment;
+    deposit_commitment.user = ctx.accounts.signer.key();
+    deposit_commitment.commitment = commitment;
+    deposit_commitment.committed_slot = committed_slot;
+    deposit_commitment.bump = ctx.bumps.deposit_commitment;
+
+    emit_digested(
+        &mut ctx.accounts.stake_operations_config,
+        DepositCommitted {
+            user: ctx.accounts.signer.key(),
+            commitment,
+            committed_slot,
+            reveal_after_slot,
+        },
+    );
+
+    msg!("deposit committed at slot {}", committed_slot);
     Ok(())
 }
 
+/// Executes a committed deposit once commit_reveal_delay_slots have passed. Shares are priced
+/// at the reveal slot, exactly like a plain deposit. The commitment account is closed.
+pub fn reveal_deposit(ctx: Context<RevealDeposit>, amount: u64, salt: [u8; 32]) -> Result<()> {
+    let operations_config = &ctx.accounts.deposit.stake_operations_config;
+    require!(
+        operations_config.commit_reveal_enabled,
+        CustomErrorCode::CommitRevealDisabled
+    );
+
+    let deposit_commitment = &ctx.accounts.deposit_commitment;
+    let reveal_after_slot = deposit_commitment
+        .committed_slot
+        .checked_add(operations_config.commit_reveal_delay_slots)
+        .ok_or(CustomErrorCode::Overflow)?;
+    require!(
+        Clock::get()?.slot >= reveal_after_slot,
+        CustomErrorCode::RevealTooEarly
+    );
+
+    let user = ctx.accounts.deposit.signer.key();
+    let revealed = hashv(&[user.as_ref(), &amount.to_le_bytes(), &salt]).to_bytes();
+    require!(
+        revealed == deposit_commitment.commitment,
+        CustomErrorCode::CommitmentMismatch
+    );
+
+    execute_deposit(
+        &mut ctx.accounts.deposit,
+        ctx.bumps.deposit.mint_authority,
+        ctx.remaining_accounts,
+        amount,
+    )
+}
+
 // Redeem stake tokens (PRIME) for vault tokens (wYLDS).
 // Burns the user's PRIME and transfers the proportional share of wYLDS from the vault.
 // Any legacy unbonding ticket (from the old two-step flow) is automatically closed
 // and rent returned to the user when the optional ticket account is provided.
+// A circuit-breaker trip in auto-pause mode returns Ok without redeeming: it only pauses the
+// protocol and emits CircuitBreakerTripped, since returning an error would undo the pause.
 pub fn redeem(ctx: Context<Redeem>, amount: u64) -> Result<()> {
     msg!("Starting redeem process");
     require!(amount > 0, CustomErrorCode::InvalidAmount);
+    // A pause older than max_pause_duration_seconds no longer blocks redeem (see MAX_PAUSE_DURATION).
     require!(
-        !ctx.accounts.stake_config.paused,
+        !ctx.accounts.stake_config.paused
+            || pause_expired(
+                ctx.accounts.stake_operations_config.paused_at,
+                &ctx.accounts.stake_operations_config
+            )?,
         CustomErrorCode::ProtocolPaused
     );
+    let operations_config = &ctx.accounts.stake_operations_config;
+    require!(
+        operations_config.paused_operations & PAUSE_REDEEM == 0
+            || pause_expired(operations_config.redeem_paused_at, operations_config)?,
+        CustomErrorCode::OperationPaused
+    );
+    // A freeze by the vault mint's own freeze authority (outside this program) would otherwise
+    // surface as an opaque token-program failure on the transfer below.
+    require!(
+        !ctx.accounts.vault_token_account.is_frozen(),
+        CustomErrorCode::VaultAccountFrozen
+    );
+
+    // Redeem cooldown: any successful redeem, partial or full, restarts the user's cooldown.
+    // The per-user state is only required while a cooldown is configured.
+    let now = Clock::get()?.unix_timestamp;
+    let cooldown = ctx.accounts.stake_operations_config.redeem_cooldown_seconds;
+    let signer_key = ctx.accounts.signer.key();
+    match (ctx.accounts.redeem_state.as_mut(), ctx.bumps.redeem_state) {
+        (Some(redeem_state), Some(bump)) => {
+            redeem_state.user = signer_key;
+            redeem_state.bump = bump;
+            if cooldown > 0 && redeem_state.last_redeem_ts > 0 {
+                require!(
+                    now.checked_sub(redeem_state.last_redeem_ts)
+                        .ok_or(CustomErrorCode::Overflow)?
+                        >= cooldown,
+                    CustomErrorCode::RedeemCooldownActive
+                );
+            }
+        }
+        _ => require!(cooldown == 0, CustomErrorCode::RedeemStateRequired),
+    }
 
     // Chainlink price-based asset calculation.
     // price convention: price = (wYLDS per 1 PRIME) * price_scale
     // Formula: wYLDS_returned = shares_burned * price / price_scale
     // Check price validity before user balance so oracle failures surface clearly.
-    let price_config = &ctx.accounts.stake_price_config;
-    let current_time = Clock::get()?.unix_timestamp;
-    require!(
-        price_config.price_timestamp > 0,
-        CustomErrorCode::PriceNotInitialized
-    );
-    // Same staleness basis as deposit: age from the stored observations_timestamp, not the verify tx time.
-    require!(
-        current_time
-            .checked_sub(price_config.price_timestamp)
-            .ok_or(CustomErrorCode::Overflow)?
-            <= price_config.price_max_staleness,
-        CustomErrorCode::PriceTooStale
-    );
-    require!(price_config.price > 0, CustomErrorCode::PriceNotInitialized);
+    // An active rate override replaces the oracle price (and its staleness checks).
+    let (price, price_scale) = match ctx.accounts.stake_operations_config.rate_override_1e9 {
+        Some(rate) => (rate as i128, EXCHANGE_RATE_SCALE),
+        None => {
+            let price_config = &ctx.accounts.stake_price_config;
+            let current_time = Clock::get()?.unix_timestamp;
+            require!(
+                price_config.price_timestamp > 0,
+                CustomErrorCode::PriceNotInitialized
+            );
+            // Same staleness basis as deposit: age from the stored observations_timestamp, not the verify tx time.
+            require!(
+                current_time
+                    .checked_sub(price_config.price_timestamp)
+                    .ok_or(CustomErrorCode::Overflow)?
+                    <= price_config.price_max_staleness,
+                CustomErrorCode::PriceTooStale
+            );
+            require!(price_config.price > 0, CustomErrorCode::PriceNotInitialized);
+            (price_config.price, price_config.price_scale)
+        }
+    };
 
     let user_share_mint_balance = ctx.accounts.user_mint_token_account.amount;
     require!(
@@ -225,22 +842,26 @@ pub fn redeem(ctx: Context<Redeem>, amount: u64) -> Result<()> {
         CustomErrorCode::InsufficientBalance
     );
 
-    let total_assets = ctx.accounts.vault_token_account.amount;
+    let total_assets = total_vault_assets(
+        ctx.accounts.vault_token_account.amount,
+        &ctx.accounts.stake_operations_config,
+    )?;
     let total_shares = ctx.accounts.mint.supply;
     msg!("total_assets: {}", total_assets);
     msg!("total_shares: {}", total_shares);
     msg!("redeem amount (shares): {}", amount);
 
-    let amount_to_withdraw = (amount as u128)
-        .checked_mul(price_config.price as u128)
-        .ok_or(CustomErrorCode::Overflow)?
-        .checked_div(price_config.price_scale as u128)
+    let redeem_numerator = (amount as u128)
+        .checked_mul(price as u128)
+        .ok_or(CustomErrorCode::Overflow)?;
+    let amount_to_withdraw = redeem_numerator
+        .checked_div(price_scale as u128)
         .ok_or(CustomErrorCode::DivisionByZero)?;
 
     msg!("Amount to withdraw calculated: {}", amount_to_withdraw);
 
-    // Guard against dust amounts rounding down to zero
-    require!(amount_to_withdraw > 0, CustomErrorCode::InvalidAmount);
+    // Guard against dust amounts rounding down to zero: the shares would be burned for nothing
+    require!(amount_to_withdraw > 0, CustomErrorCode::RedeemTooSmall);
 
     let amount_to_withdraw_u64: u64 = amount_to_withdraw
         .try_into()
@@ -251,6 +872,58 @@ pub fn redeem(ctx: Context<Redeem>, amount: u64) -> Result<()> {
         CustomErrorCode::InsufficientVaultBalance
     );
 
+    // Circuit breaker: a single redeem withdrawing more than max_single_outflow_bps of total
+    // assets is rejected, or (auto-pause mode) pauses the protocol without redeeming anything.
+    let outflow_bps = bps_of(amount_to_withdraw_u64, total_assets)?;
+    let operations_config = &mut ctx.accounts.stake_operations_config;
+    if operations_config.max_single_outflow_bps > 0
+        && outflow_bps > operations_config.max_single_outflow_bps
+    {
+        require!(
+            operations_config.circuit_breaker_auto_pause,
+            CustomErrorCode::SingleOutflowLimitExceeded
+        );
+        if !ctx.accounts.stake_config.paused {
+            ctx.accounts.stake_config.paused = true;
+            operations_config.paused_at = Clock::get()?.unix_timestamp;
+        }
+        let max_single_outflow_bps = operations_config.max_single_outflow_bps;
+        emit_digested(
+            operations_config,
+            CircuitBreakerTripped {
+                user: ctx.accounts.signer.key(),
+                requested_assets: amount_to_withdraw_u64,
+                total_assets,
+                outflow_bps,
+                max_single_outflow_bps,
+                stake_config: ctx.accounts.stake_config.key(),
+            },
+        );
+        msg!(
+            "circuit breaker tripped: {} bps > {} bps, protocol paused",
+            outflow_bps,
+            max_single_outflow_bps
+        );
+        return Ok(());
+    }
+    let operations_config = &mut ctx.accounts.stake_operations_config;
+    operations_config.largest_single_outflow_bps = operations_config
+        .largest_single_outflow_bps
+        .max(outflow_bps);
+    accrue_rounding_dust(
+        operations_config,
+        redeem_numerator % price_scale as u128,
+        price_scale,
+    )?;
+    require!(
+        amount_to_withdraw_u64
+            <= max_redeemable_assets(
+                ctx.accounts.vault_token_account.amount,
+                &ctx.accounts.stake_operations_config,
+            )?,
+        CustomErrorCode::ReserveFloorBreached
+    );
+
     let burn_accounts = Burn {
         mint: ctx.accounts.mint.to_account_info(),
         from: ctx.accounts.user_mint_token_account.to_account_info(),
@@ -277,30 +950,125 @@ pub fn redeem(ctx: Context<Redeem>, amount: u64) -> Result<()> {
         amount_to_withdraw_u64,
     )?;
 
+    check_redeem_consistency(amount, amount_to_withdraw_u64, price, price_scale)?;
+    if let Some(redeem_state) = ctx.accounts.redeem_state.as_mut() {
+        redeem_state.last_redeem_ts = now;
+    }
+
     let result_total_assets = total_assets
         .checked_sub(amount_to_withdraw_u64)
         .ok_or(CustomErrorCode::Overflow)?;
     let result_total_shares = total_shares
         .checked_sub(amount)
         .ok_or(CustomErrorCode::Overflow)?;
-    let totals_last_update_slot = Clock::get()?.slot;
+    let totals_last_update_slot = record_totals_update(&mut ctx.accounts.stake_operations_config)?;
+    // The ticket itself is closed by Anchor (close = signer) once the instruction succeeds.
+    let reclaimed_rent_lamports = ctx
+        .accounts
+        .ticket
+        .as_ref()
+        .map_or(0, |ticket| ticket.to_account_info().lamports());
 
     msg!("Emitting RedeemEvent");
-    emit!(RedeemEvent {
-        user: ctx.accounts.signer.key(),
-        mint: ctx.accounts.mint.key(),
-        requested_mint_amount: amount,
-        mint_supply: ctx.accounts.mint.supply,
-        vault: ctx.accounts.vault_token_account.key(),
-        redeemed_vault_amount: amount_to_withdraw_u64,
-        vault_balance: ctx.accounts.vault_token_account.amount,
-        shares_burned: amount,
-        total_assets: result_total_assets,
-        total_shares: result_total_shares,
-        totals_last_update_slot,
-    });
+    emit_digested(
+        &mut ctx.accounts.stake_operations_config,
+        RedeemEvent {
+            user: ctx.accounts.signer.key(),
+            mint: ctx.accounts.mint.key(),
+            requested_mint_amount: amount,
+            mint_supply: ctx.accounts.mint.supply,
+            vault: ctx.accounts.vault_token_account.key(),
+            redeemed_vault_amount: amount_to_withdraw_u64,
+            vault_balance: ctx.accounts.vault_token_account.amount,
+            shares_burned: amount,
+            total_assets: result_total_assets,
+            total_shares: result_total_shares,
+            totals_last_update_slot,
+            reclaimed_rent_lamports,
+        },
+    );
     msg!("Emitted RedeemEvent");
 
+    invoke_hook(
+        &ctx.accounts.stake_operations_config,
+        ctx.remaining_accounts,
+        StakeHookPayload {
+            kind: StakeHookKind::Redeem,
+            user: ctx.accounts.signer.key(),
+            assets: amount_to_withdraw_u64,
+            shares: amount,
+            total_assets: result_total_assets,
+            total_shares: result_total_shares,
+        },
+    )
+}
+
+// Donate vault tokens (wYLDS) to the vault without receiving shares.
+// Share pricing is oracle-driven, so a donation does not move the deposit/redeem price;
+// it raises the assets backing every outstanding share and is recorded in total_donated.
+// Donations are accepted while paused: they only add assets (e.g. to make users whole).
+pub fn donate(ctx: Context<Donate>, amount: u64) -> Result<()> {
+    require!(amount > 0, CustomErrorCode::InvalidAmount);
+
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.donor_vault_token_account.to_account_info(),
+        to: ctx.accounts.vault_token_account.to_account_info(),
+        authority: ctx.accounts.donor.to_account_info(),
+    };
+    token::transfer(
+        CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts),
+        amount,
+    )?;
+
+    let operations_config = &mut ctx.accounts.stake_operations_config;
+    operations_config.total_donated = operations_config
+        .total_donated
+        .checked_add(amount)
+        .ok_or(CustomErrorCode::Overflow)?;
+
+    ctx.accounts.vault_token_account.reload()?;
+
+    let total_assets = total_vault_assets(
+        ctx.accounts.vault_token_account.amount,
+        &ctx.accounts.stake_operations_config,
+    )?;
+    let total_shares = ctx.accounts.mint.supply;
+    let assets_per_share = if total_shares == 0 {
+        0
+    } else {
+        (total_assets as u128)
+            .checked_mul(1_000_000_000)
+            .ok_or(CustomErrorCode::Overflow)?
+            .checked_div(total_shares as u128)
+            .ok_or(CustomErrorCode::DivisionByZero)?
+            .to_u64()
+            .ok_or(CustomErrorCode::Overflow)?
+    };
+    let totals_last_update_slot = record_totals_update(&mut ctx.accounts.stake_operations_config)?;
+
+    msg!(
+        "Donation of {} received from {}",
+        amount,
+        ctx.accounts.donor.key()
+    );
+    msg!("Emitting DonationEvent");
+    let total_donated = ctx.accounts.stake_operations_config.total_donated;
+    emit_digested(
+        &mut ctx.accounts.stake_operations_config,
+        DonationEvent {
+            donor: ctx.accounts.donor.key(),
+            amount,
+            vault: ctx.accounts.vault_token_account.key(),
+            vault_balance: ctx.accounts.vault_token_account.amount,
+            total_assets,
+            total_shares,
+            assets_per_share,
+            total_donated,
+            totals_last_update_slot,
+        },
+    );
+    msg!("Emitted DonationEvent");
+
     Ok(())
 }
 
@@ -309,18 +1077,25 @@ pub fn redeem(ctx: Context<Redeem>, amount: u64) -> Result<()> {
 pub fn update_freeze_administrators(
     ctx: Context<UpdateFreezeAdministrators>,
     new_administrators: Vec<Pubkey>,
+    expected_version: Option<u64>,
 ) -> Result<()> {
     // Validate that the signer is the program's update authority
     validate_program_update_authority(&ctx.accounts.program_data, &ctx.accounts.signer)?;
 
     let config = &mut ctx.accounts.stake_config;
 
+    validate_administrators(&new_administrators)?;
     require!(
-        new_administrators.len() <= MAX_ADMINISTRATORS,
-        CustomErrorCode::TooManyAdministrators
+        !(ctx
+            .accounts
+            .stake_operations_config
+            .require_nonempty_freeze_admins
+            && new_administrators.is_empty()),
+        CustomErrorCode::CannotRemoveLastAdministrator
     );
 
     config.freeze_administrators = new_administrators;
+    bump_admin_list_version(&mut ctx.accounts.stake_operations_config, expected_version)?;
 
     msg!(
         "Freeze administrators updated. New count: {}",
@@ -334,18 +1109,25 @@ pub fn update_freeze_administrators(
 pub fn update_rewards_administrators(
     ctx: Context<UpdateRewardsAdministrators>,
     new_administrators: Vec<Pubkey>,
+    expected_version: Option<u64>,
 ) -> Result<()> {
     // Validate that the signer is the program's update authority
     validate_program_update_authority(&ctx.accounts.program_data, &ctx.accounts.signer)?;
 
     let config = &mut ctx.accounts.stake_config;
 
+    validate_administrators(&new_administrators)?;
     require!(
-        new_administrators.len() <= MAX_ADMINISTRATORS,
-        CustomErrorCode::TooManyAdministrators
+        !(ctx
+            .accounts
+            .stake_operations_config
+            .require_nonempty_rewards_admins
+            && new_administrators.is_empty()),
+        CustomErrorCode::CannotRemoveLastAdministrator
     );
 
     config.rewards_administrators = new_administrators;
+    bump_admin_list_version(&mut ctx.accounts.stake_operations_config, expected_version)?;
 
     msg!(
         "Rewards administrators updated. New count: {}",
@@ -354,24 +1136,91 @@ pub fn update_rewards_administrators(
     Ok(())
 }
 
-// Freeze a specific token account (only freeze administrators can do this)
-pub fn freeze_token_account(ctx: Context<FreezeTokenAccount>) -> Result<()> {
-    let config = &ctx.accounts.stake_config;
-    let signer = ctx.accounts.signer.key();
+/// Copies the StakeOperationsConfig fields execute_deposit changes from the cloned Deposit used
+/// by deposit_with_ata and deposit_vesting back into the instruction's own account.
+fn copy_deposit_updates(target: &mut StakeOperationsConfig, deposited: &StakeOperationsConfig) {
+    target.rounding_dust_accumulated = deposited.rounding_dust_accumulated;
+    target.events_digest = deposited.events_digest;
+    target.last_totals_update_slot = deposited.last_totals_update_slot;
+}
 
-    // Verify signer is a freeze administrator
-    require!(
-        config.freeze_administrators.contains(&signer),
-        CustomErrorCode::UnauthorizedFreezeAdministrator
-    );
+/// Records that total assets or the stake supply changed in the current slot and returns it.
+fn record_totals_update(operations_config: &mut StakeOperationsConfig) -> Result<u64> {
+    let slot = Clock::get()?.slot;
+    operations_config.last_totals_update_slot = slot;
+    Ok(slot)
+}
 
-    let freeze_authority_seeds: &[&[&[u8]]] =
-        &[&[b"freeze_authority", &[ctx.bumps.freeze_authority_pda]]];
+/// Emits `event` like emit! and folds its logged bytes into events_digest, so indexers can check
+/// they replayed the complete event stream by recomputing the hash.
+fn emit_digested<E: anchor_lang::Event>(operations_config: &mut StakeOperationsConfig, event: E) {
+    let data = event.data();
+    operations_config.events_digest = hashv(&[&operations_config.events_digest, &data]).to_bytes();
+    anchor_lang::solana_program::log::sol_log_data(&[&data]);
+}
 
-    let cpi_accounts = token::FreezeAccount {
-        account: ctx.accounts.token_account.to_account_info(),
-        mint: ctx.accounts.mint.to_account_info(),
-        authority: ctx.accounts.freeze_authority_pda.to_account_info(),
+/// Administrator lists hold at most MAX_ADMINISTRATORS distinct, non-default keys.
+fn validate_administrators(administrators: &[Pubkey]) -> Result<()> {
+    require!(
+        administrators.len() <= MAX_ADMINISTRATORS,
+        CustomErrorCode::TooManyAdministrators
+    );
+    for (i, administrator) in administrators.iter().enumerate() {
+        require!(
+            *administrator != Pubkey::default(),
+            CustomErrorCode::InvalidAdministrator
+        );
+        require!(
+            !administrators[..i].contains(administrator),
+            CustomErrorCode::DuplicateAdministrator
+        );
+    }
+    Ok(())
+}
+
+/// Optimistic concurrency for the admin lists: when expected_version is Some it must equal the
+/// current admin_list_version, so an update built from a stale read fails instead of silently
+/// overwriting a concurrent one. Every successful update increments the version.
+fn bump_admin_list_version(
+    operations_config: &mut StakeOperationsConfig,
+    expected_version: Option<u64>,
+) -> Result<()> {
+    if let Some(expected_version) = expected_version {
+        require!(
+            expected_version == operations_config.admin_list_version,
+            CustomErrorCode::AdminListVersionMismatch
+        );
+    }
+    operations_config.admin_list_version = operations_config
+        .admin_list_version
+        .checked_add(1)
+        .ok_or(CustomErrorCode::Overflow)?;
+    msg!(
+        "Admin list version: {}",
+        operations_config.admin_list_version
+    );
+    Ok(())
+}
+
+// Freeze a specific token account (only freeze administrators can do this)
+pub fn freeze_token_account(ctx: Context<FreezeTokenAccount>) -> Result<()> {
+    let config = &ctx.accounts.stake_config;
+    let signer = ctx.accounts.signer.key();
+
+    // Verify signer is a freeze administrator
+    require!(
+        config.freeze_administrators.contains(&signer),
+        CustomErrorCode::UnauthorizedFreezeAdministrator
+    );
+    require_operation_active(&ctx.accounts.stake_operations_config, PAUSE_FREEZE)?;
+
+    let freeze_authority_seeds: &[&[&[u8]]] =
+        &[&[b"freeze_authority", &[ctx.bumps.freeze_authority_pda]]];
+
+    let cpi_accounts = token::FreezeAccount {
+        account: ctx.accounts.token_account.to_account_info(),
+        mint: ctx.accounts.mint.to_account_info(),
+        authority: ctx.accounts.freeze_authority_pda.to_account_info(),
     };
 
     let cpi_ctx = CpiContext::new_with_signer(
@@ -426,66 +1275,371 @@ pub fn thaw_token_account(ctx: Context<ThawTokenAccount>) -> Result<()> {
     Ok(())
 }
 
-pub fn publish_rewards(ctx: Context<PublishRewards>, id: u32, amount: u64) -> Result<()> {
+// Compliance restriction: freeze a token account and record the reason, who and when in its
+// RestrictionRecord PDA (only freeze administrators can do this).
+pub fn restrict_account(ctx: Context<RestrictAccount>, reason: String) -> Result<()> {
+    let signer = ctx.accounts.signer.key();
     require!(
-        !ctx.accounts.stake_config.paused,
-        CustomErrorCode::ProtocolPaused
+        ctx.accounts
+            .stake_config
+            .freeze_administrators
+            .contains(&signer),
+        CustomErrorCode::UnauthorizedFreezeAdministrator
+    );
+    require_operation_active(&ctx.accounts.stake_operations_config, PAUSE_FREEZE)?;
+    require!(
+        reason.len() <= MAX_RESTRICTION_REASON_LEN,
+        CustomErrorCode::RestrictionReasonTooLong
+    );
+    require!(
+        !ctx.accounts.restriction_record.is_active(),
+        CustomErrorCode::AccountAlreadyRestricted
+    );
+
+    let freeze_authority_seeds: &[&[&[u8]]] =
+        &[&[b"freeze_authority", &[ctx.bumps.freeze_authority_pda]]];
+    let cpi_accounts = token::FreezeAccount {
+        account: ctx.accounts.token_account.to_account_info(),
+        mint: ctx.accounts.mint.to_account_info(),
+        authority: ctx.accounts.freeze_authority_pda.to_account_info(),
+    };
+    token::freeze_account(CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        cpi_accounts,
+        freeze_authority_seeds,
+    ))?;
+
+    let restricted_at = Clock::get()?.unix_timestamp;
+    let record = &mut ctx.accounts.restriction_record;
+    record.token_account = ctx.accounts.token_account.key();
+    record.reason = reason.clone();
+    record.restricted_by = signer;
+    record.restricted_at = restricted_at;
+    record.lifted_by = Pubkey::default();
+    record.lifted_at = 0;
+    record.bump = ctx.bumps.restriction_record;
+
+    emit_digested(
+        &mut ctx.accounts.stake_operations_config,
+        AccountRestricted {
+            token_account: ctx.accounts.token_account.key(),
+            owner: ctx.accounts.token_account.owner,
+            reason,
+            restricted_by: signer,
+            restricted_at,
+        },
+    );
+
+    msg!(
+        "Token account {} restricted by administrator {}",
+        ctx.accounts.token_account.key(),
+        signer
     );
+    Ok(())
+}
+
+// Lift a compliance restriction: thaw the token account and record who lifted it and when.
+// The record is kept as the audit trail of the latest restriction.
+pub fn lift_restriction(ctx: Context<LiftRestriction>) -> Result<()> {
+    let signer = ctx.accounts.signer.key();
     require!(
         ctx.accounts
             .stake_config
-            .rewards_administrators
-            .contains(&ctx.accounts.admin.key()),
-        CustomErrorCode::InvalidRewardsAdministrator
+            .freeze_administrators
+            .contains(&signer),
+        CustomErrorCode::UnauthorizedFreezeAdministrator
+    );
+    require!(
+        ctx.accounts.restriction_record.is_active(),
+        CustomErrorCode::AccountNotRestricted
     );
-    require!(amount > 0, CustomErrorCode::InvalidAmount);
 
-    let config = &mut ctx.accounts.stake_reward_config;
+    let freeze_authority_seeds: &[&[&[u8]]] =
+        &[&[b"freeze_authority", &[ctx.bumps.freeze_authority_pda]]];
+    let cpi_accounts = token::ThawAccount {
+        account: ctx.accounts.token_account.to_account_info(),
+        mint: ctx.accounts.mint.to_account_info(),
+        authority: ctx.accounts.freeze_authority_pda.to_account_info(),
+    };
+    token::thaw_account(CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        cpi_accounts,
+        freeze_authority_seeds,
+    ))?;
+
+    let lifted_at = Clock::get()?.unix_timestamp;
+    let record = &mut ctx.accounts.restriction_record;
+    record.lifted_by = signer;
+    record.lifted_at = lifted_at;
+
+    emit_digested(
+        &mut ctx.accounts.stake_operations_config,
+        RestrictionLifted {
+            token_account: ctx.accounts.token_account.key(),
+            owner: ctx.accounts.token_account.owner,
+            reason: record.reason.clone(),
+            restricted_by: record.restricted_by,
+            restricted_at: record.restricted_at,
+            lifted_by: signer,
+            lifted_at,
+        },
+    );
 
-    // Enforce reward cap: amount must not exceed max_reward_bps % of current total_assets.
-    // Skip only when the vault is truly empty (bootstrap) — cap applies whenever assets exist.
-    let total_assets = ctx.accounts.vault_token_account.amount;
-    if total_assets > 0 {
-        let effective_bps = config.max_reward_bps;
-        let max_allowed = (total_assets as u128)
-            .checked_mul(effective_bps as u128)
-            .and_then(|v| v.checked_div(StakeRewardConfig::MAX_BPS as u128))
-            .and_then(|v| v.to_u64())
-            .ok_or(CustomErrorCode::Overflow)?;
-        require!(
-            amount <= max_allowed,
-            CustomErrorCode::RewardExceedsMaxDelta
+    msg!(
+        "Restriction on token account {} lifted by administrator {}",
+        ctx.accounts.token_account.key(),
+        signer
+    );
+    Ok(())
+}
+
+// Hand the stake mint's freeze authority to the freeze_authority PDA so freeze/thaw work
+// before they are first needed. The signer must be both the program upgrade authority and the
+// mint's current freeze authority; nothing changes if the PDA already holds it.
+pub fn claim_freeze_authority(ctx: Context<ClaimFreezeAuthority>) -> Result<()> {
+    validate_program_update_authority(&ctx.accounts.program_data, &ctx.accounts.signer)?;
+
+    let freeze_authority_pda = ctx.accounts.freeze_authority_pda.key();
+    let current_authority = ctx.accounts.mint.freeze_authority;
+    if current_authority == Some(freeze_authority_pda).into() {
+        msg!("Freeze authority already held by {}", freeze_authority_pda);
+        return Ok(());
+    }
+    require!(
+        current_authority == Some(ctx.accounts.signer.key()).into(),
+        CustomErrorCode::InvalidFreezeAuthority
+    );
+
+    token::set_authority(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            token::SetAuthority {
+                account_or_mint: ctx.accounts.mint.to_account_info(),
+                current_authority: ctx.accounts.signer.to_account_info(),
+            },
+        ),
+        AuthorityType::FreezeAccount,
+        Some(freeze_authority_pda),
+    )?;
+
+    msg!(
+        "Freeze authority of mint {} transferred to {}",
+        ctx.accounts.mint.key(),
+        freeze_authority_pda
+    );
+    Ok(())
+}
+
+/// Returns true when the stake mint's freeze authority is the freeze_authority PDA,
+/// i.e. freeze_token_account and thaw_token_account can succeed.
+pub fn validate_freeze_authority(ctx: Context<FreezeAuthorityView>) -> Result<bool> {
+    let is_set =
+        ctx.accounts.mint.freeze_authority == Some(ctx.accounts.freeze_authority_pda.key()).into();
+
+    msg!("freeze authority held by program: {}", is_set);
+
+    Ok(is_set)
+}
+
+/// Reports whether freeze/thaw will work: who holds the stake mint's freeze authority and how
+/// many freeze administrators are configured. Meant to be checked at setup, not mid-incident.
+pub fn validate_freeze_setup(ctx: Context<FreezeAuthorityView>) -> Result<FreezeSetup> {
+    let freeze_authority: Option<Pubkey> = ctx.accounts.mint.freeze_authority.into();
+    let status = match freeze_authority {
+        Some(authority) if authority == ctx.accounts.freeze_authority_pda.key() => {
+            FreezeAuthorityStatus::ProgramControlled
+        }
+        Some(_) => FreezeAuthorityStatus::External,
+        None => FreezeAuthorityStatus::None,
+    };
+    let freeze_administrator_count = ctx.accounts.stake_config.freeze_administrators.len() as u8;
+    let ready =
+        status == FreezeAuthorityStatus::ProgramControlled && freeze_administrator_count > 0;
+
+    msg!(
+        "freeze setup: {:?} (authority {:?}), {} freeze administrators, ready: {}",
+        status,
+        freeze_authority,
+        freeze_administrator_count,
+        ready
+    );
+
+    Ok(FreezeSetup {
+        status,
+        freeze_authority,
+        freeze_administrator_count,
+        ready,
+    })
+}
+
+/// Audits up to MAX_VAULT_OWNERSHIP_ACCOUNTS token accounts passed as remaining accounts: for each
+/// it reports the mint, whether the vault authority PDA owns it, and whether it is the canonical
+/// vault token account or the insurance account. Every account must be an SPL token account.
+pub fn verify_vault_ownership(ctx: Context<VaultOwnershipView>) -> Result<VaultOwnershipReport> {
+    require!(
+        ctx.remaining_accounts.len() <= MAX_VAULT_OWNERSHIP_ACCOUNTS,
+        CustomErrorCode::TooManyAccounts
+    );
+
+    let vault_authority = ctx.accounts.vault_authority.key();
+    let canonical_account = ctx
+        .accounts
+        .stake_vault_token_account_config
+        .vault_token_account;
+    let insurance_account = ctx.accounts.stake_operations_config.insurance_token_account;
+
+    let mut entries = Vec::with_capacity(ctx.remaining_accounts.len());
+    let mut unexpected_count: u8 = 0;
+    for account_info in ctx.remaining_accounts.iter() {
+        require_keys_eq!(
+            *account_info.owner,
+            token::ID,
+            ErrorCode::AccountOwnedByWrongProgram
         );
+        let token_account = TokenAccount::try_deserialize(&mut &account_info.data.borrow()[..])?;
+        let entry = VaultOwnershipEntry {
+            mint: token_account.mint,
+            owned_by_vault_authority: token_account.owner == vault_authority,
+            canonical: account_info.key() == canonical_account,
+            insurance: insurance_account != Pubkey::default()
+                && account_info.key() == insurance_account,
+        };
+        if entry.owned_by_vault_authority && !entry.canonical && !entry.insurance {
+            msg!("unexpected vault-authority account: {}", account_info.key());
+            unexpected_count += 1;
+        }
+        entries.push(entry);
     }
 
-    // Absolute per-call cap.
+    msg!(
+        "verify_vault_ownership: {} accounts, {} unexpected",
+        entries.len(),
+        unexpected_count
+    );
+
+    Ok(VaultOwnershipReport {
+        entries,
+        unexpected_count,
+    })
+}
+
+/// Checks the vault's core invariants in one read-only call for monitoring bots and returns a
+/// mask of the HEALTH_* conditions found (0 means healthy). Solvency compares total assets
+/// (idle + deployed) with the stake supply valued at the price deposit/redeem would use.
+pub fn health_check(ctx: Context<HealthCheck>) -> Result<u32> {
+    let accounts = &ctx.accounts;
+    let price_config = &accounts.stake_price_config;
+    let mut status: u32 = 0;
+
+    if accounts.stake_config.paused {
+        status |= HEALTH_PAUSED;
+    }
+    if accounts.vault_token_account.owner != accounts.vault_authority.key() {
+        status |= HEALTH_VAULT_NOT_PROGRAM_OWNED;
+    }
+
+    let price = match accounts.stake_operations_config.rate_override_1e9 {
+        Some(rate) => {
+            status |= HEALTH_RATE_OVERRIDE_ACTIVE;
+            Some((rate as i128, EXCHANGE_RATE_SCALE))
+        }
+        None if price_config.price <= 0 || price_config.price_timestamp == 0 => {
+            status |= HEALTH_PRICE_NOT_INITIALIZED;
+            None
+        }
+        None => {
+            let age = Clock::get()?
+                .unix_timestamp
+                .checked_sub(price_config.price_timestamp)
+                .ok_or(CustomErrorCode::Overflow)?;
+            if age > price_config.price_max_staleness {
+                status |= HEALTH_PRICE_STALE;
+            }
+            Some((price_config.price, price_config.price_scale))
+        }
+    };
+
+    if let Some((price, price_scale)) = price {
+        let liabilities = quoted_assets_for_shares(accounts.mint.supply, price, price_scale)?;
+        let tolerance = liabilities
+            .checked_mul(HEALTH_SOLVENCY_TOLERANCE_BPS as u128)
+            .ok_or(CustomErrorCode::Overflow)?
+            / StakeOperationsConfig::MAX_BPS as u128;
+        let total_assets = total_vault_assets(
+            accounts.vault_token_account.amount,
+            &accounts.stake_operations_config,
+        )? as u128;
+        if total_assets + tolerance < liabilities {
+            status |= HEALTH_UNDERCOLLATERALIZED;
+        }
+    }
+
+    msg!("health_check: status {:#08b}", status);
+
+    Ok(status)
+}
+
+/// Returns the frozen state of up to MAX_FREEZE_STATUS_ACCOUNTS stake token accounts passed as
+/// remaining accounts, as a bitmask: bit i is set when remaining_accounts[i] is frozen.
+/// Every account must be a token account of the stake mint.
+pub fn freeze_status_many(ctx: Context<StakeMintView>) -> Result<u64> {
     require!(
-        amount <= config.max_period_rewards,
-        CustomErrorCode::ExceedsPeriodRewardCap
+        ctx.remaining_accounts.len() <= MAX_FREEZE_STATUS_ACCOUNTS,
+        CustomErrorCode::TooManyAccounts
     );
 
-    // Cooldown between reward publications (first publication is always allowed).
-    let now = Clock::get()?.unix_timestamp;
-    if config.last_reward_distributed_at > 0 {
-        let next_allowed_at = config
-            .last_reward_distributed_at
-            .checked_add(config.reward_period_seconds)
-            .ok_or(CustomErrorCode::Overflow)?;
+    let mut frozen_mask: u64 = 0;
+    for (i, account_info) in ctx.remaining_accounts.iter().enumerate() {
+        require_keys_eq!(
+            *account_info.owner,
+            token::ID,
+            ErrorCode::AccountOwnedByWrongProgram
+        );
+        let token_account = TokenAccount::try_deserialize(&mut &account_info.data.borrow()[..])?;
         require!(
-            now >= next_allowed_at,
-            CustomErrorCode::RewardCooldownNotElapsed
+            token_account.mint == ctx.accounts.mint.key(),
+            CustomErrorCode::InvalidMint
         );
+        if token_account.is_frozen() {
+            frozen_mask |= 1 << i;
+        }
     }
 
-    // Lifetime cap.
-    let next_total = config
-        .total_rewards_distributed
-        .checked_add(amount)
-        .ok_or(CustomErrorCode::Overflow)?;
+    msg!(
+        "freeze_status_many: {} accounts, mask {:#x}",
+        ctx.remaining_accounts.len(),
+        frozen_mask
+    );
+    Ok(frozen_mask)
+}
+
+pub fn publish_rewards<'info>(
+    ctx: Context<'_, '_, 'info, 'info, PublishRewards<'info>>,
+    id: u32,
+    amount: u64,
+) -> Result<()> {
+    // Fail clearly instead of with an opaque CPI error once the mint program is retired;
+    // publish_rewards_from_reserve remains available.
     require!(
-        next_total <= config.max_total_rewards,
-        CustomErrorCode::ExceedsLifetimeRewardCap
+        ctx.accounts.stake_operations_config.external_mint_enabled,
+        CustomErrorCode::ExternalMintDisabled
     );
+    let total_assets = total_vault_assets(
+        ctx.accounts.vault_token_account.amount,
+        &ctx.accounts.stake_operations_config,
+    )?;
+    let (now, next_total) = authorize_reward_publication(
+        &ctx.accounts.stake_config,
+        &ctx.accounts.stake_operations_config,
+        &ctx.accounts.stake_reward_config,
+        &mut ctx.accounts.stake_reward_policy_config,
+        ctx.accounts.admin.key(),
+        Some(id),
+        amount,
+        total_assets,
+        ctx.accounts.mint.supply,
+    )?;
+    let config = &mut ctx.accounts.stake_reward_config;
 
     // Initialize the reward record
     let reward_record = &mut ctx.accounts.reward_record;
@@ -533,88 +1687,725 @@ pub fn publish_rewards(ctx: Context<PublishRewards>, id: u32, amount: u64) -> Re
     // reload the vault token account to get the updated amount for publishing the event
     ctx.accounts.vault_token_account.reload()?;
 
-    let totals_last_update_slot = Clock::get()?.slot;
+    let result_total_assets = total_vault_assets(
+        ctx.accounts.vault_token_account.amount,
+        &ctx.accounts.stake_operations_config,
+    )?;
+    let totals_last_update_slot = record_totals_update(&mut ctx.accounts.stake_operations_config)?;
 
     msg!("Publishing rewards for id: {} for amount: {}", id, amount);
     msg!("Emitting RewardsPublished");
-    emit!(RewardsPublished {
-        admin: ctx.accounts.admin.key(),
-        amount,
-        mint_program: ctx.accounts.mint_program.key(),
-        vault_token_account: ctx.accounts.vault_token_account.key(),
-        mint: stake_config.mint,
-        vault: stake_config.vault,
-        total_assets: ctx.accounts.vault_token_account.amount,
-        total_shares: ctx.accounts.mint.supply,
-        totals_last_update_slot,
-        id,
-    });
+    emit_digested(
+        &mut ctx.accounts.stake_operations_config,
+        RewardsPublished {
+            admin: ctx.accounts.admin.key(),
+            amount,
+            mint_program: ctx.accounts.mint_program.key(),
+            vault_token_account: ctx.accounts.vault_token_account.key(),
+            mint: stake_config.mint,
+            vault: stake_config.vault,
+            total_assets: result_total_assets,
+            total_shares: ctx.accounts.mint.supply,
+            totals_last_update_slot,
+            id,
+        },
+    );
     msg!("Emitted RewardsPublished");
 
+    // Amortized cleanup: the caller may pass one expired record to close.
+    if let Some(record_info) = ctx.remaining_accounts.first() {
+        let stake_config_key = ctx.accounts.stake_config.key();
+        prune_reward_record(
+            record_info,
+            &ctx.accounts.admin,
+            &mut ctx.accounts.stake_operations_config,
+            stake_config_key,
+            now,
+        )?;
+    }
+
     Ok(())
 }
 
-/// FOR TESTING ONLY — directly writes price and price_timestamp into StakePriceConfig.
-/// Requires program upgrade authority. Intended for localnet test environments where
-/// the Chainlink verifier is not available. DO NOT USE IN PRODUCTION.
-#[cfg(feature = "testing")]
-pub fn set_price_for_testing(
-    ctx: Context<SetPriceForTesting>,
-    price: i128,
-    price_timestamp: i64,
+/// Publishes rewards by moving `amount` from the reward reserve into the vault instead of minting
+/// through vault-mint. Shares reward ids, records and every cap with publish_rewards.
+pub fn publish_rewards_from_reserve(
+    ctx: Context<PublishRewardsFromReserve>,
+    id: u32,
+    amount: u64,
 ) -> Result<()> {
-    validate_program_update_authority(&ctx.accounts.program_data, &ctx.accounts.signer)?;
-    let config = &mut ctx.accounts.stake_price_config;
-    config.price = price;
-    config.price_timestamp = price_timestamp;
-    msg!(
-        "set_price_for_testing: price={}, price_timestamp={}",
-        price,
-        price_timestamp
+    let total_assets = total_vault_assets(
+        ctx.accounts.vault_token_account.amount,
+        &ctx.accounts.stake_operations_config,
+    )?;
+    let (now, next_total) = authorize_reward_publication(
+        &ctx.accounts.stake_config,
+        &ctx.accounts.stake_operations_config,
+        &ctx.accounts.stake_reward_config,
+        &mut ctx.accounts.stake_reward_policy_config,
+        ctx.accounts.admin.key(),
+        Some(id),
+        amount,
+        total_assets,
+        ctx.accounts.mint.supply,
+    )?;
+    require!(
+        ctx.accounts.reward_reserve_token_account.amount >= amount,
+        CustomErrorCode::InsufficientRewardReserve
     );
-    Ok(())
-}
 
-/// Convert shares to underlying assets using the stored Chainlink price.
-/// assets = shares * price / price_scale
-/// Returns value via return_data for efficient CPI access
-pub fn shares_to_assets(ctx: Context<ConversionView>, shares: u64) -> Result<u64> {
-    let price_config = &ctx.accounts.stake_price_config;
-    require!(price_config.price > 0, CustomErrorCode::PriceNotInitialized);
+    let reward_record = &mut ctx.accounts.reward_record;
+    reward_record.id = id;
+    reward_record.amount = amount;
+    reward_record.published_at = now;
+    reward_record.bump = ctx.bumps.reward_record;
 
-    let assets = (shares as u128)
-        .checked_mul(price_config.price as u128)
-        .ok_or(CustomErrorCode::Overflow)?
-        .checked_div(price_config.price_scale as u128)
-        .ok_or(CustomErrorCode::DivisionByZero)? as u64;
+    let seeds: &[&[u8]] = &[b"vault_authority", &[ctx.bumps.vault_authority]];
+    let signer = &[&seeds[..]];
+    let transfer_accounts = Transfer {
+        from: ctx.accounts.reward_reserve_token_account.to_account_info(),
+        to: ctx.accounts.vault_token_account.to_account_info(),
+        authority: ctx.accounts.vault_authority.to_account_info(),
+    };
+    token::transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            transfer_accounts,
+            signer,
+        ),
+        amount,
+    )?;
 
-    msg!("shares_to_assets: {} shares = {} assets", shares, assets);
+    let config = &mut ctx.accounts.stake_reward_config;
+    config.last_reward_distributed_at = now;
+    config.total_rewards_distributed = next_total;
 
-    anchor_lang::solana_program::program::set_return_data(&assets.to_le_bytes());
+    ctx.accounts.reward_reserve_token_account.reload()?;
+    ctx.accounts.vault_token_account.reload()?;
+    let result_total_assets = total_vault_assets(
+        ctx.accounts.vault_token_account.amount,
+        &ctx.accounts.stake_operations_config,
+    )?;
+    let totals_last_update_slot = record_totals_update(&mut ctx.accounts.stake_operations_config)?;
 
-    Ok(assets)
+    let stake_config = &ctx.accounts.stake_config;
+    msg!(
+        "Publishing rewards from reserve for id: {} for amount: {}",
+        id,
+        amount
+    );
+    emit_digested(
+        &mut ctx.accounts.stake_operations_config,
+        RewardsPublished {
+            admin: ctx.accounts.admin.key(),
+            amount,
+            mint_program: Pubkey::default(),
+            vault_token_account: ctx.accounts.vault_token_account.key(),
+            mint: stake_config.mint,
+            vault: stake_config.vault,
+            total_assets: result_total_assets,
+            total_shares: ctx.accounts.mint.supply,
+            totals_last_update_slot,
+            id,
+        },
+    );
+    emit_digested(
+        &mut ctx.accounts.stake_operations_config,
+        RewardReserveDrawn {
+            id,
+            amount,
+            reward_reserve_token_account: ctx.accounts.reward_reserve_token_account.key(),
+            reserve_balance: ctx.accounts.reward_reserve_token_account.amount,
+            stake_config: stake_config.key(),
+        },
+    );
+    Ok(())
 }
 
-/// Convert underlying assets to shares using the stored Chainlink price.
-/// shares = assets * price_scale / price
-/// Returns value via return_data for efficient CPI access
-pub fn assets_to_shares(ctx: Context<ConversionView>, assets: u64) -> Result<u64> {
-    let price_config = &ctx.accounts.stake_price_config;
-    require!(price_config.price > 0, CustomErrorCode::PriceNotInitialized);
-
-    let shares = (assets as u128)
-        .checked_mul(price_config.price_scale as u128)
-        .ok_or(CustomErrorCode::Overflow)?
-        .checked_div(price_config.price as u128)
-        .ok_or(CustomErrorCode::DivisionByZero)? as u64;
-
-    msg!("assets_to_shares: {} assets = {} shares", assets, shares);
-
-    anchor_lang::solana_program::program::set_return_data(&shares.to_le_bytes());
+/// Checks shared by every reward publication path: pause state, administrator, reward id, supply
+/// floor, the per-call, period and lifetime caps, the cooldown and the admin's budget (which it
+/// charges). Publications with a reward `id` also count a new reward record. Returns the current
+/// time and the new lifetime total.
+#[allow(clippy::too_many_arguments)]
+fn authorize_reward_publication(
+    stake_config: &StakeConfig,
+    operations_config: &StakeOperationsConfig,
+    config: &StakeRewardConfig,
+    policy: &mut StakeRewardPolicyConfig,
+    admin: Pubkey,
+    id: Option<u32>,
+    amount: u64,
+    total_assets: u64,
+    stake_supply: u64,
+) -> Result<(i64, u64)> {
+    require!(!stake_config.paused, CustomErrorCode::ProtocolPaused);
+    require_operation_active(operations_config, PAUSE_PUBLISH_REWARDS)?;
+    require!(
+        stake_config.rewards_administrators.contains(&admin),
+        CustomErrorCode::InvalidRewardsAdministrator
+    );
+    require!(
+        !policy.disabled_admins.contains(&admin),
+        CustomErrorCode::RewardsAdminDisabled
+    );
+    if let Some(id) = id {
+        advance_reward_id(policy, id)?;
+    }
+    require!(amount > 0, CustomErrorCode::InvalidAmount);
+
+    // With only a sliver of supply outstanding, a reward would accrue almost entirely to whoever
+    // holds it; min_supply_for_rewards (0 = off) holds publication until the stake is broad enough.
+    require!(
+        stake_supply >= operations_config.min_supply_for_rewards,
+        CustomErrorCode::InsufficientSupplyForRewards
+    );
+
+    // Enforce reward cap: amount must not exceed max_reward_bps % of current total_assets.
+    // Skip only when the vault is truly empty (bootstrap) — cap applies whenever assets exist.
+    // total_assets includes assets reported as deployed outside the vault token account.
+    if total_assets > 0 {
+        let effective_bps = config.max_reward_bps;
+        let max_allowed = (total_assets as u128)
+            .checked_mul(effective_bps as u128)
+            .and_then(|v| v.checked_div(StakeRewardConfig::MAX_BPS as u128))
+            .and_then(|v| v.to_u64())
+            .ok_or(CustomErrorCode::Overflow)?;
+        require!(
+            amount <= max_allowed,
+            CustomErrorCode::RewardExceedsMaxDelta
+        );
+    }
+
+    // Absolute per-call cap.
+    require!(
+        amount <= config.max_period_rewards,
+        CustomErrorCode::ExceedsPeriodRewardCap
+    );
+
+    // Cooldown between reward publications (first publication is always allowed).
+    let now = Clock::get()?.unix_timestamp;
+    if config.last_reward_distributed_at > 0 {
+        let next_allowed_at = config
+            .last_reward_distributed_at
+            .checked_add(config.reward_period_seconds)
+            .ok_or(CustomErrorCode::Overflow)?;
+        require!(
+            now >= next_allowed_at,
+            CustomErrorCode::RewardCooldownNotElapsed
+        );
+    }
+
+    // Lifetime cap.
+    let next_total = config
+        .total_rewards_distributed
+        .checked_add(amount)
+        .ok_or(CustomErrorCode::Overflow)?;
+    require!(
+        next_total <= config.max_total_rewards,
+        CustomErrorCode::ExceedsLifetimeRewardCap
+    );
+
+    // Per-admin budget (administrators without a budget entry are not budget-limited).
+    if let Some(index) = policy.budget_admins.iter().position(|a| *a == admin) {
+        let remaining = policy.budget_remaining[index];
+        require!(amount <= remaining, CustomErrorCode::AdminBudgetExceeded);
+        policy.budget_remaining[index] = remaining - amount;
+    }
+    if id.is_some() {
+        policy.reward_record_count = policy
+            .reward_record_count
+            .checked_add(1)
+            .ok_or(CustomErrorCode::Overflow)?;
+    }
+
+    Ok((now, next_total))
+}
+
+/// Closes a RewardPublicationRecord older than reward_record_retention, refunding its rent to
+/// `rent_recipient`. Records within retention (all of them while it is 0) are rejected.
+fn prune_reward_record<'info>(
+    record_info: &'info AccountInfo<'info>,
+    rent_recipient: &Signer<'info>,
+    operations_config: &mut StakeOperationsConfig,
+    stake_config: Pubkey,
+    now: i64,
+) -> Result<()> {
+    // Checks the owner and discriminator, so only this program's reward records can be closed.
+    let record = Account::<RewardPublicationRecord>::try_from(record_info)?;
+    let retention = operations_config.reward_record_retention;
+    let age = now
+        .checked_sub(record.published_at)
+        .ok_or(CustomErrorCode::Overflow)?;
+    require!(
+        retention > 0 && age > retention,
+        CustomErrorCode::RewardRecordWithinRetention
+    );
+
+    let pruned = RewardRecordPruned {
+        id: record.id,
+        amount: record.amount,
+        published_at: record.published_at,
+        record: record_info.key(),
+        rent_recipient: rent_recipient.key(),
+        stake_config,
+    };
+    record.close(rent_recipient.to_account_info())?;
+
+    msg!("Pruned reward record {} (age {}s)", pruned.id, age);
+    emit_digested(operations_config, pruned);
+    Ok(())
+}
+
+/// Stores the Merkle root of holder balances an off-chain indexer read at `slot`.
+/// Only callable by an enabled rewards administrator; each slot can be committed once.
+pub fn commit_snapshot(
+    ctx: Context<CommitSnapshot>,
+    merkle_root: [u8; 32],
+    total_shares_snapshot: u64,
+    slot: u64,
+) -> Result<()> {
+    let admin = ctx.accounts.admin.key();
+    require!(
+        ctx.accounts
+            .stake_config
+            .rewards_administrators
+            .contains(&admin),
+        CustomErrorCode::InvalidRewardsAdministrator
+    );
+    require!(
+        !ctx.accounts
+            .stake_reward_policy_config
+            .disabled_admins
+            .contains(&admin),
+        CustomErrorCode::RewardsAdminDisabled
+    );
+    require!(
+        merkle_root != [0u8; 32] && slot <= Clock::get()?.slot,
+        CustomErrorCode::InvalidSnapshot
+    );
+
+    let snapshot_root = &mut ctx.accounts.snapshot_root;
+    snapshot_root.merkle_root = merkle_root;
+    snapshot_root.total_shares_snapshot = total_shares_snapshot;
+    snapshot_root.slot = slot;
+    snapshot_root.committed_by = admin;
+    snapshot_root.committed_at = Clock::get()?.unix_timestamp;
+    snapshot_root.bump = ctx.bumps.snapshot_root;
+
+    emit_digested(
+        &mut ctx.accounts.stake_operations_config,
+        SnapshotCommitted {
+            admin,
+            merkle_root,
+            total_shares_snapshot,
+            slot,
+            stake_config: ctx.accounts.stake_config.key(),
+        },
+    );
+    msg!(
+        "snapshot committed for slot {} ({} shares)",
+        slot,
+        total_shares_snapshot
+    );
+    Ok(())
+}
+
+/// Returns whether `holder` held `shares` in the snapshot:
+/// leaf = sha256(holder || shares_le || slot_le) must prove up to the committed root.
+pub fn verify_snapshot_claim(
+    ctx: Context<SnapshotView>,
+    holder: Pubkey,
+    shares: u64,
+    proof: Vec<ProofNode>,
+) -> Result<bool> {
+    let snapshot_root = &ctx.accounts.snapshot_root;
+    let leaf = hashv(&[
+        holder.as_ref(),
+        &shares.to_le_bytes(),
+        &snapshot_root.slot.to_le_bytes(),
+    ])
+    .to_bytes();
+    let valid = shares <= snapshot_root.total_shares_snapshot
+        && merkle_root_from_proof(leaf, &proof) == snapshot_root.merkle_root;
+
+    msg!(
+        "snapshot claim of {} for {} shares at slot {}: {}",
+        holder,
+        shares,
+        snapshot_root.slot,
+        valid
+    );
+    anchor_lang::solana_program::program::set_return_data(&[valid as u8]);
+
+    Ok(valid)
+}
+
+/// Publishes a stake-token airdrop: holders later claim extra PRIME against `merkle_root`
+/// (leaf = sha256(claimer || extra_shares_le)), up to `total_shares` in all. Rewards
+/// administrators only, within the reward caps, cooldown and budgets (see below).
+pub fn publish_stake_reward(
+    ctx: Context<PublishStakeReward>,
+    merkle_root: [u8; 32],
+    total_shares: u64,
+) -> Result<()> {
+    let admin = ctx.accounts.admin.key();
+    require!(
+        merkle_root != [0u8; 32] && total_shares > 0,
+        CustomErrorCode::InvalidStakeReward
+    );
+
+    // Claims mint up to total_shares of unbacked PRIME that redeem pays out at the current price,
+    // so the distribution is charged at that value against the same caps and budgets as
+    // publish_rewards.
+    let (price, price_scale) = preview_price(
+        &ctx.accounts.stake_operations_config,
+        &ctx.accounts.stake_price_config,
+    )?;
+    let value: u64 = quoted_assets_for_shares(total_shares, price, price_scale)?
+        .try_into()
+        .map_err(|_| CustomErrorCode::Overflow)?;
+    let total_assets = total_vault_assets(
+        ctx.accounts.vault_token_account.amount,
+        &ctx.accounts.stake_operations_config,
+    )?;
+    let (now, next_total) = authorize_reward_publication(
+        &ctx.accounts.stake_config,
+        &ctx.accounts.stake_operations_config,
+        &ctx.accounts.stake_reward_config,
+        &mut ctx.accounts.stake_reward_policy_config,
+        admin,
+        None,
+        value,
+        total_assets,
+        ctx.accounts.mint.supply,
+    )?;
+    let config = &mut ctx.accounts.stake_reward_config;
+    config.last_reward_distributed_at = now;
+    config.total_rewards_distributed = next_total;
+
+    let distribution = &mut ctx.accounts.stake_reward_distribution;
+    distribution.merkle_root = merkle_root;
+    distribution.total_shares = total_shares;
+    distribution.claimed_shares = 0;
+    distribution.published_by = admin;
+    distribution.published_at = now;
+    distribution.bump = ctx.bumps.stake_reward_distribution;
+
+    emit_digested(
+        &mut ctx.accounts.stake_operations_config,
+        StakeRewardPublished {
+            admin,
+            merkle_root,
+            total_shares,
+            stake_config: ctx.accounts.stake_config.key(),
+        },
+    );
+    msg!("stake reward published: {} shares", total_shares);
+    Ok(())
+}
+
+/// Mints the signer's `extra_shares` from a published stake reward once they prove
+/// leaf = sha256(signer || extra_shares_le) against its root. The ClaimRecord PDA prevents a
+/// second claim.
+pub fn claim_stake_reward(
+    ctx: Context<ClaimStakeReward>,
+    extra_shares: u64,
+    proof: Vec<ProofNode>,
+) -> Result<()> {
+    require!(
+        !ctx.accounts.stake_config.paused,
+        CustomErrorCode::ProtocolPaused
+    );
+    require!(extra_shares > 0, CustomErrorCode::InvalidAmount);
+
+    let user = ctx.accounts.signer.key();
+    let distribution = &ctx.accounts.stake_reward_distribution;
+    let leaf = hashv(&[user.as_ref(), &extra_shares.to_le_bytes()]).to_bytes();
+    require!(
+        merkle_root_from_proof(leaf, &proof) == distribution.merkle_root,
+        CustomErrorCode::InvalidMerkleProof
+    );
+    let claimed_shares = distribution
+        .claimed_shares
+        .checked_add(extra_shares)
+        .ok_or(CustomErrorCode::Overflow)?;
+    require!(
+        claimed_shares <= distribution.total_shares,
+        CustomErrorCode::StakeRewardExhausted
+    );
+    if let Some(max_total_shares) = ctx.accounts.stake_operations_config.max_total_shares {
+        let result_total_shares = ctx
+            .accounts
+            .mint
+            .supply
+            .checked_add(extra_shares)
+            .ok_or(CustomErrorCode::Overflow)?;
+        require!(
+            result_total_shares <= max_total_shares,
+            CustomErrorCode::MaxSharesExceeded
+        );
+    }
+
+    let seeds: &[&[u8]] = &[b"mint_authority", &[ctx.bumps.mint_authority]];
+    let signer = &[&seeds[..]];
+    token::mint_to(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            MintTo {
+                mint: ctx.accounts.mint.to_account_info(),
+                to: ctx.accounts.user_mint_token_account.to_account_info(),
+                authority: ctx.accounts.mint_authority.to_account_info(),
+            },
+            signer,
+        ),
+        extra_shares,
+    )?;
+
+    let distribution = &mut ctx.accounts.stake_reward_distribution;
+    distribution.claimed_shares = claimed_shares;
+    let merkle_root = distribution.merkle_root;
+    record_totals_update(&mut ctx.accounts.stake_operations_config)?;
+
+    emit_digested(
+        &mut ctx.accounts.stake_operations_config,
+        StakeRewardClaimed {
+            user,
+            merkle_root,
+            extra_shares,
+            claimed_shares,
+            stake_config: ctx.accounts.stake_config.key(),
+        },
+    );
+    msg!(
+        "stake reward claimed: {} shares ({} claimed)",
+        extra_shares,
+        claimed_shares
+    );
+    Ok(())
+}
+
+/// Publishes a zero-amount "heartbeat" reward record so off-chain consumers see a regular epoch
+/// tick even when there are no rewards. Nothing is minted, and the reward caps, cooldown and
+/// budgets are left untouched.
+pub fn publish_epoch_marker(ctx: Context<PublishEpochMarker>, id: u32) -> Result<()> {
+    require!(
+        !ctx.accounts.stake_config.paused,
+        CustomErrorCode::ProtocolPaused
+    );
+    require_operation_active(&ctx.accounts.stake_operations_config, PAUSE_PUBLISH_REWARDS)?;
+    require!(
+        ctx.accounts
+            .stake_config
+            .rewards_administrators
+            .contains(&ctx.accounts.admin.key()),
+        CustomErrorCode::InvalidRewardsAdministrator
+    );
+    require!(
+        !ctx.accounts
+            .stake_reward_policy_config
+            .disabled_admins
+            .contains(&ctx.accounts.admin.key()),
+        CustomErrorCode::RewardsAdminDisabled
+    );
+    advance_reward_id(&mut ctx.accounts.stake_reward_policy_config, id)?;
+
+    let reward_record = &mut ctx.accounts.reward_record;
+    reward_record.id = id;
+    reward_record.amount = 0;
+    reward_record.published_at = Clock::get()?.unix_timestamp;
+    reward_record.bump = ctx.bumps.reward_record;
+
+    let policy = &mut ctx.accounts.stake_reward_policy_config;
+    policy.reward_record_count = policy
+        .reward_record_count
+        .checked_add(1)
+        .ok_or(CustomErrorCode::Overflow)?;
+
+    let total_assets = total_vault_assets(
+        ctx.accounts.vault_token_account.amount,
+        &ctx.accounts.stake_operations_config,
+    )?;
+
+    // a marker moves no assets, so the totals keep their last update slot
+    let totals_last_update_slot = ctx.accounts.stake_operations_config.last_totals_update_slot;
+
+    msg!("Publishing epoch marker for id: {}", id);
+    emit_digested(
+        &mut ctx.accounts.stake_operations_config,
+        RewardsPublished {
+            admin: ctx.accounts.admin.key(),
+            amount: 0,
+            mint_program: Pubkey::default(), // no mint CPI
+            vault_token_account: ctx.accounts.vault_token_account.key(),
+            mint: ctx.accounts.stake_config.mint,
+            vault: ctx.accounts.stake_config.vault,
+            total_assets,
+            total_shares: ctx.accounts.mint.supply,
+            totals_last_update_slot,
+            id,
+        },
+    );
+
+    Ok(())
+}
+
+/// FOR TESTING ONLY — directly writes price and price_timestamp into StakePriceConfig.
+/// Requires program upgrade authority. Intended for localnet test environments where
+/// the Chainlink verifier is not available. DO NOT USE IN PRODUCTION.
+#[cfg(feature = "testing")]
+pub fn set_price_for_testing(
+    ctx: Context<SetPriceForTesting>,
+    price: i128,
+    price_timestamp: i64,
+) -> Result<()> {
+    validate_program_update_authority(&ctx.accounts.program_data, &ctx.accounts.signer)?;
+    let config = &mut ctx.accounts.stake_price_config;
+    config.price = price;
+    config.price_timestamp = price_timestamp;
+    msg!(
+        "set_price_for_testing: price={}, price_timestamp={}",
+        price,
+        price_timestamp
+    );
+    Ok(())
+}
+
+/// FOR TESTING ONLY — writes StakeConfig.paused without recording paused_at, reproducing a vault
+/// paused before StakeOperationsConfig existed. DO NOT USE IN PRODUCTION.
+#[cfg(feature = "testing")]
+pub fn set_paused_for_testing(ctx: Context<SetPausedForTesting>, paused: bool) -> Result<()> {
+    validate_program_update_authority(&ctx.accounts.program_data, &ctx.accounts.signer)?;
+    ctx.accounts.stake_config.paused = paused;
+    msg!("set_paused_for_testing: paused={}", paused);
+    Ok(())
+}
+
+/// Convert shares to underlying assets at the price redeem uses (rate override or stored price).
+/// assets = shares * price / price_scale
+/// Returns value (with both mints' decimals) via return_data for efficient CPI access
+pub fn shares_to_assets(ctx: Context<ConversionView>, shares: u64) -> Result<ConversionResult> {
+    let (price, price_scale) = preview_price(
+        &ctx.accounts.stake_operations_config,
+        &ctx.accounts.stake_price_config,
+    )?;
+    let assets = assets_for_shares_quote(price, price_scale, shares)?;
+
+    msg!("shares_to_assets: {} shares = {} assets", shares, assets);
+
+    // Anchor serializes the returned struct into return_data.
+    conversion_result(ctx.accounts, assets)
+}
+
+/// Convert underlying assets to shares at the price deposit uses (rate override or stored price).
+/// shares = assets * price_scale / price
+/// Returns value (with both mints' decimals) via return_data for efficient CPI access
+pub fn assets_to_shares(ctx: Context<ConversionView>, assets: u64) -> Result<ConversionResult> {
+    let (price, price_scale) = preview_price(
+        &ctx.accounts.stake_operations_config,
+        &ctx.accounts.stake_price_config,
+    )?;
+    let shares = shares_for_assets_quote(price, price_scale, assets)?;
+
+    msg!("assets_to_shares: {} assets = {} shares", assets, shares);
+
+    // Anchor serializes the returned struct into return_data.
+    conversion_result(ctx.accounts, shares)
+}
+
+/// Both conversions in one view: `shares_for_assets` is what assets_to_shares(assets) returns
+/// and `assets_for_shares` what shares_to_assets(shares) returns, for dual-quote UIs.
+pub fn convert_both(
+    ctx: Context<ConversionView>,
+    assets: u64,
+    shares: u64,
+) -> Result<ConvertBothResult> {
+    let (price, price_scale) = preview_price(
+        &ctx.accounts.stake_operations_config,
+        &ctx.accounts.stake_price_config,
+    )?;
+    let shares_for_assets = shares_for_assets_quote(price, price_scale, assets)?;
+    let assets_for_shares = assets_for_shares_quote(price, price_scale, shares)?;
+    let slot = Clock::get()?.slot;
+
+    msg!(
+        "convert_both: {} assets = {} shares, {} shares = {} assets",
+        assets,
+        shares_for_assets,
+        shares,
+        assets_for_shares
+    );
+
+    Ok(ConvertBothResult {
+        shares_for_assets,
+        assets_for_shares,
+        slot,
+    })
+}
 
+/// shares = assets * price_scale / price, as quoted by assets_to_shares.
+fn shares_for_assets_quote(price: i128, price_scale: u64, assets: u64) -> Result<u64> {
+    require!(price > 0, CustomErrorCode::PriceNotInitialized);
+
+    // Large `assets` at a tiny price can exceed u64; fail with Overflow instead of truncating.
+    let shares: u64 = (assets as u128)
+        .checked_mul(price_scale as u128)
+        .ok_or(CustomErrorCode::Overflow)?
+        .checked_div(price as u128)
+        .ok_or(CustomErrorCode::DivisionByZero)?
+        .try_into()
+        .map_err(|_| CustomErrorCode::Overflow)?;
     Ok(shares)
 }
 
+/// assets = shares * price / price_scale, as quoted by shares_to_assets.
+fn assets_for_shares_quote(price: i128, price_scale: u64, shares: u64) -> Result<u64> {
+    require!(price > 0, CustomErrorCode::PriceNotInitialized);
+
+    // A quote that does not fit in u64 is an error, not a silently truncated amount.
+    let assets: u64 = quoted_assets_for_shares(shares, price, price_scale)?
+        .try_into()
+        .map_err(|_| CustomErrorCode::Overflow)?;
+    Ok(assets)
+}
+
+/// Vault assets to deposit so the holder's stake token balance reaches `target_shares` at the
+/// deposit price (rate override or stored price): ceil((target_shares - balance) * price /
+/// price_scale), the smallest deposit whose minted shares (floor(assets * price_scale / price))
+/// cover the shortfall. Returns 0 when the balance already meets the target.
+pub fn assets_for_target_shares(ctx: Context<TargetSharesView>, target_shares: u64) -> Result<u64> {
+    let (price, price_scale) = preview_price(
+        &ctx.accounts.stake_operations_config,
+        &ctx.accounts.stake_price_config,
+    )?;
+    require!(price > 0, CustomErrorCode::PriceNotInitialized);
+
+    let balance = ctx.accounts.user_mint_token_account.amount;
+    let missing_shares = target_shares.saturating_sub(balance);
+    let assets: u64 = (missing_shares as u128)
+        .checked_mul(price as u128)
+        .ok_or(CustomErrorCode::Overflow)?
+        .div_ceil(price_scale as u128)
+        .try_into()
+        .map_err(|_| CustomErrorCode::Overflow)?;
+
+    msg!(
+        "assets_for_target_shares: balance {} -> target {} needs {} assets",
+        balance,
+        target_shares,
+        assets
+    );
+    Ok(assets)
+}
+
+fn conversion_result(accounts: &ConversionView, amount: u64) -> Result<ConversionResult> {
+    Ok(ConversionResult {
+        amount,
+        vault_decimals: accounts.vault_mint.decimals,
+        share_decimals: accounts.mint.decimals,
+        slot: Clock::get()?.slot,
+    })
+}
+
 /// Initializes the StakePriceConfig PDA.
 /// Must be called once after program upgrade, before any deposit or redeem.
 /// Only callable by the program upgrade authority.
@@ -686,11 +2477,14 @@ pub fn update_price_config(
         config.price = 0;
         config.price_timestamp = 0;
         msg!("Stored price invalidated due to feed_id/price_scale change");
-        emit!(PriceInvalidated {
-            verifier: ctx.accounts.signer.key(),
-            feed_id: feed_id,
-            price_scale: price_scale,
-        });
+        emit_digested(
+            &mut ctx.accounts.stake_operations_config,
+            PriceInvalidated {
+                verifier: ctx.accounts.signer.key(),
+                feed_id: feed_id,
+                price_scale: price_scale,
+            },
+        );
     }
 
     Ok(())
@@ -733,12 +2527,15 @@ pub fn update_max_reward_bps(ctx: Context<UpdateMaxRewardBps>, new_bps: u64) ->
     let old_bps = config.max_reward_bps;
     config.max_reward_bps = new_bps;
 
-    emit!(MaxRewardBpsUpdated {
-        admin: ctx.accounts.signer.key(),
-        old_bps,
-        new_bps,
-        stake_config: ctx.accounts.stake_config.key(),
-    });
+    emit_digested(
+        &mut ctx.accounts.stake_operations_config,
+        MaxRewardBpsUpdated {
+            admin: ctx.accounts.signer.key(),
+            old_bps,
+            new_bps,
+            stake_config: ctx.accounts.stake_config.key(),
+        },
+    );
 
     msg!("max_reward_bps updated: {} -> {}", old_bps, new_bps);
     Ok(())
@@ -752,12 +2549,15 @@ pub fn update_max_period_rewards(ctx: Context<UpdateMaxPeriodRewards>, new_cap:
     let old_value = config.max_period_rewards;
     config.max_period_rewards = new_cap;
 
-    emit!(MaxPeriodRewardsUpdated {
-        admin: ctx.accounts.signer.key(),
-        old_value,
-        new_value: new_cap,
-        stake_config: ctx.accounts.stake_config.key(),
-    });
+    emit_digested(
+        &mut ctx.accounts.stake_operations_config,
+        MaxPeriodRewardsUpdated {
+            admin: ctx.accounts.signer.key(),
+            old_value,
+            new_value: new_cap,
+            stake_config: ctx.accounts.stake_config.key(),
+        },
+    );
 
     msg!("max_period_rewards updated: {} -> {}", old_value, new_cap);
     Ok(())
@@ -776,12 +2576,15 @@ pub fn update_reward_period_seconds(
     let old_value = config.reward_period_seconds;
     config.reward_period_seconds = new_seconds;
 
-    emit!(RewardPeriodSecondsUpdated {
-        admin: ctx.accounts.signer.key(),
-        old_value,
-        new_value: new_seconds,
-        stake_config: ctx.accounts.stake_config.key(),
-    });
+    emit_digested(
+        &mut ctx.accounts.stake_operations_config,
+        RewardPeriodSecondsUpdated {
+            admin: ctx.accounts.signer.key(),
+            old_value,
+            new_value: new_seconds,
+            stake_config: ctx.accounts.stake_config.key(),
+        },
+    );
 
     msg!(
         "reward_period_seconds updated: {} -> {}",
@@ -805,12 +2608,15 @@ pub fn update_max_total_rewards(ctx: Context<UpdateMaxTotalRewards>, new_cap: u6
     let old_value = config.max_total_rewards;
     config.max_total_rewards = new_cap;
 
-    emit!(MaxTotalRewardsUpdated {
-        admin: ctx.accounts.signer.key(),
-        old_value,
-        new_value: new_cap,
-        stake_config: ctx.accounts.stake_config.key(),
-    });
+    emit_digested(
+        &mut ctx.accounts.stake_operations_config,
+        MaxTotalRewardsUpdated {
+            admin: ctx.accounts.signer.key(),
+            old_value,
+            new_value: new_cap,
+            stake_config: ctx.accounts.stake_config.key(),
+        },
+    );
 
     msg!("max_total_rewards updated: {} -> {}", old_value, new_cap);
     Ok(())
@@ -928,36 +2734,1516 @@ pub fn verify_price(ctx: Context<VerifyPrice>, signed_report: Vec<u8>) -> Result
     msg!("expires_at: {}", report.expires_at);
 
     msg!("Emitting PriceVerifiedEvent");
-    emit!(PriceVerifiedEvent {
-        verifier: ctx.accounts.signer.key(),
-        feed_id: report.feed_id.0,
-        price: price_config.price,
-        price_scale: price_config.price_scale,
-        price_timestamp: observation_ts,
-        expires_at: report.expires_at as u64,
-        slot: Clock::get()?.slot,
-    });
+    emit_digested(
+        &mut ctx.accounts.stake_operations_config,
+        PriceVerifiedEvent {
+            verifier: ctx.accounts.signer.key(),
+            feed_id: report.feed_id.0,
+            price: price_config.price,
+            price_scale: price_config.price_scale,
+            price_timestamp: observation_ts,
+            expires_at: report.expires_at as u64,
+            slot: Clock::get()?.slot,
+        },
+    );
 
     Ok(())
 }
 
-/// Get current exchange rate from stored Chainlink price.
+/// Get the current exchange rate: the rate override when active, otherwise the stored Chainlink
+/// price.
 /// Returns assets per share scaled by 1e9: price * 1_000_000_000 / price_scale
 /// Example: if 1 PRIME = 1.5 wYLDS, returns 1_500_000_000
-pub fn exchange_rate(ctx: Context<ConversionView>) -> Result<u64> {
-    let price_config = &ctx.accounts.stake_price_config;
-    require!(price_config.price > 0, CustomErrorCode::PriceNotInitialized);
+pub fn exchange_rate(ctx: Context<ConversionView>) -> Result<ExchangeRateResult> {
+    let (price, price_scale) = preview_price(
+        &ctx.accounts.stake_operations_config,
+        &ctx.accounts.stake_price_config,
+    )?;
+    let rate = calculate_exchange_rate(price, price_scale)?;
+    let slot = Clock::get()?.slot;
+
+    msg!("exchange_rate: {} (scaled by 1e9)", rate);
+
+    // Anchor serializes the returned struct into return_data; rate is the leading u64.
+    Ok(ExchangeRateResult { rate, slot })
+}
 
-    const SCALE: u128 = 1_000_000_000;
-    let rate = (price_config.price as u128)
-        .checked_mul(SCALE)
+/// Get the exchange rate split into whole wYLDS per PRIME and the fractional remainder
+/// (scaled by 1e9), so integer-only clients need no floating point.
+pub fn exchange_rate_parts(ctx: Context<ConversionView>) -> Result<ExchangeRateParts> {
+    let (price, price_scale) = preview_price(
+        &ctx.accounts.stake_operations_config,
+        &ctx.accounts.stake_price_config,
+    )?;
+    let rate = calculate_exchange_rate(price, price_scale)?;
+    let parts = ExchangeRateParts {
+        whole: rate / EXCHANGE_RATE_SCALE,
+        frac_1e9: rate % EXCHANGE_RATE_SCALE,
+        slot: Clock::get()?.slot,
+    };
+
+    msg!(
+        "exchange_rate_parts: whole={}, frac_1e9={}",
+        parts.whole,
+        parts.frac_1e9
+    );
+
+    Ok(parts)
+}
+
+/// Get the exchange rate in the Pyth price/expo/conf shape for oracle relayers.
+pub fn price_feed(ctx: Context<ConversionView>) -> Result<PriceFeed> {
+    let (price, price_scale) = preview_price(
+        &ctx.accounts.stake_operations_config,
+        &ctx.accounts.stake_price_config,
+    )?;
+    let rate = calculate_exchange_rate(price, price_scale)?;
+    let feed = PriceFeed {
+        price: rate.try_into().map_err(|_| CustomErrorCode::Overflow)?,
+        expo: -(EXCHANGE_RATE_SCALE.ilog10() as i32),
+        conf: PRICE_FEED_CONFIDENCE,
+        slot: Clock::get()?.slot,
+    };
+
+    msg!(
+        "price_feed: price={}, expo={}, conf={}",
+        feed.price,
+        feed.expo,
+        feed.conf
+    );
+
+    Ok(feed)
+}
+
+/// Get the UI-ready "1 PRIME = X wYLDS" rate (and its inverse) adjusted for both mints'
+/// decimals, so every front-end displays the same figures.
+pub fn display_rate(ctx: Context<ConversionView>) -> Result<DisplayRate> {
+    let (price, price_scale) = preview_price(
+        &ctx.accounts.stake_operations_config,
+        &ctx.accounts.stake_price_config,
+    )?;
+    require!(price > 0, CustomErrorCode::PriceNotInitialized);
+
+    let vault_decimals = ctx.accounts.vault_mint.decimals;
+    let share_decimals = ctx.accounts.mint.decimals;
+    let vault_unit = 10u128
+        .checked_pow(vault_decimals as u32)
+        .ok_or(CustomErrorCode::Overflow)?;
+    let share_unit = 10u128
+        .checked_pow(share_decimals as u32)
+        .ok_or(CustomErrorCode::Overflow)?;
+    let price = price as u128;
+    let price_scale = price_scale as u128;
+    let scale = EXCHANGE_RATE_SCALE as u128;
+
+    // whole wYLDS per whole PRIME = (price / price_scale) * share_unit / vault_unit
+    let assets_per_share_1e9 = price
+        .checked_mul(scale)
+        .and_then(|v| v.checked_mul(share_unit))
+        .ok_or(CustomErrorCode::Overflow)?
+        .checked_div(
+            price_scale
+                .checked_mul(vault_unit)
+                .ok_or(CustomErrorCode::Overflow)?,
+        )
+        .ok_or(CustomErrorCode::DivisionByZero)?
+        .to_u64()
+        .ok_or(CustomErrorCode::Overflow)?;
+    // whole PRIME per whole wYLDS = (price_scale / price) * vault_unit / share_unit
+    let shares_per_asset_1e9 = price_scale
+        .checked_mul(scale)
+        .and_then(|v| v.checked_mul(vault_unit))
         .ok_or(CustomErrorCode::Overflow)?
-        .checked_div(price_config.price_scale as u128)
-        .ok_or(CustomErrorCode::DivisionByZero)? as u64;
+        .checked_div(
+            price
+                .checked_mul(share_unit)
+                .ok_or(CustomErrorCode::Overflow)?,
+        )
+        .ok_or(CustomErrorCode::DivisionByZero)?
+        .to_u64()
+        .ok_or(CustomErrorCode::Overflow)?;
 
-    msg!("exchange_rate: {} (scaled by 1e9)", rate);
+    let slot = Clock::get()?.slot;
+
+    msg!(
+        "display_rate: 1 PRIME = {} wYLDS, 1 wYLDS = {} PRIME (scaled by 1e9)",
+        assets_per_share_1e9,
+        shares_per_asset_1e9
+    );
+
+    Ok(DisplayRate {
+        assets_per_share_1e9,
+        shares_per_asset_1e9,
+        vault_decimals,
+        share_decimals,
+        slot,
+    })
+}
+
+/// Get the PDA seed scheme version (SEED_SCHEME_VERSION) so clients can pick the matching
+/// derivation logic for the deployed program.
+pub fn seed_scheme(_ctx: Context<SeedSchemeView>) -> Result<u8> {
+    msg!("seed scheme: {}", SEED_SCHEME_VERSION);
+    Ok(SEED_SCHEME_VERSION)
+}
+
+/// Get the program's current upgrade authority, the key that gates the admin instructions.
+/// Returns None once the program has been made immutable.
+pub fn get_upgrade_authority(ctx: Context<GetUpgradeAuthority>) -> Result<Option<Pubkey>> {
+    let upgrade_authority = read_program_upgrade_authority(&ctx.accounts.program_data)?;
+    match upgrade_authority {
+        Some(authority) => msg!("upgrade authority: {}", authority),
+        None => msg!("upgrade authority: none (program is immutable)"),
+    }
+    Ok(upgrade_authority)
+}
+
+/// Get the state of `owner`'s legacy unbonding ticket. The caller does not need to be the owner.
+/// Tickets are no longer created and never delay a redeem; any remaining one is closed by the
+/// owner's next redeem.
+pub fn get_ticket(ctx: Context<TicketView>, owner: Pubkey) -> Result<TicketStatus> {
+    let ticket = &ctx.accounts.ticket;
+    msg!(
+        "ticket of {}: requested {} at {}",
+        owner,
+        ticket.requested_amount,
+        ticket.start_ts
+    );
+    Ok(TicketStatus {
+        requested_amount: ticket.requested_amount,
+        start_ts: ticket.start_ts,
+        start_balance: ticket.start_balance,
+    })
+}
+
+/// Closes the signer's legacy unbonding ticket and returns its rent without redeeming. Nothing is
+/// escrowed against a ticket, so no tokens move.
+pub fn cancel_unbond(ctx: Context<CancelUnbond>) -> Result<()> {
+    let owner = ctx.accounts.ticket.owner;
+    let requested_amount = ctx.accounts.ticket.requested_amount;
+
+    // The ticket itself is closed by Anchor (close = signer) once the instruction succeeds.
+    emit_digested(
+        &mut ctx.accounts.stake_operations_config,
+        UnbondCancelledEvent {
+            owner,
+            requested_amount,
+            mint: ctx.accounts.stake_config.mint,
+        },
+    );
+
+    msg!(
+        "Cancelled unbonding ticket of {} for {}",
+        owner,
+        requested_amount
+    );
+    Ok(())
+}
+
+/// Get the configured stake (share) mint and vault mint without deserializing StakeConfig.
+pub fn get_mints(ctx: Context<StakeConfigView>) -> Result<StakeMints> {
+    Ok(StakeMints {
+        mint: ctx.accounts.stake_config.mint,
+        vault: ctx.accounts.stake_config.vault,
+    })
+}
+
+/// Get the vault token account deposit, redeem and the other vault instructions must be passed;
+/// any other account fails their InvalidVaultTokenAccount constraint.
+pub fn expected_vault_token_account(ctx: Context<VaultTokenAccountConfigView>) -> Result<Pubkey> {
+    let vault_token_account = ctx
+        .accounts
+        .stake_vault_token_account_config
+        .vault_token_account;
+    msg!("expected vault token account: {}", vault_token_account);
+    Ok(vault_token_account)
+}
+
+/// Get every timing setting (redeem cooldown, price staleness, pause limit, reward period,
+/// commit-reveal delay) in one struct. unbonding_period is reported as stored, which is always 0.
+pub fn timing_params(ctx: Context<TimingParamsView>) -> Result<TimingParams> {
+    let operations_config = &ctx.accounts.stake_operations_config;
+    let params = TimingParams {
+        unbonding_period: ctx.accounts.stake_config.unbonding_period,
+        redeem_cooldown_seconds: operations_config.redeem_cooldown_seconds,
+        price_max_staleness: ctx.accounts.stake_price_config.price_max_staleness,
+        max_pause_duration_seconds: operations_config.max_pause_duration_seconds,
+        reward_period_seconds: ctx.accounts.stake_reward_config.reward_period_seconds,
+        commit_reveal_delay_slots: operations_config.commit_reveal_delay_slots,
+    };
+    msg!("timing_params: {:?}", params);
+    Ok(params)
+}
 
-    anchor_lang::solana_program::program::set_return_data(&rate.to_le_bytes());
+/// wYLDS per PRIME at `price`, scaled by EXCHANGE_RATE_SCALE (1e9).
+fn calculate_exchange_rate(price: i128, price_scale: u64) -> Result<u64> {
+    require!(price > 0, CustomErrorCode::PriceNotInitialized);
 
+    let rate = (price as u128)
+        .checked_mul(EXCHANGE_RATE_SCALE as u128)
+        .ok_or(CustomErrorCode::Overflow)?
+        .checked_div(price_scale as u128)
+        .ok_or(CustomErrorCode::DivisionByZero)?
+        .try_into()
+        .map_err(|_| CustomErrorCode::Overflow)?;
     Ok(rate)
 }
+
+/// Initializes the StakeOperationsConfig PDA with no deployed assets.
+/// Must be called once after program upgrade, before any deposit, redeem or publish_rewards.
+/// Only callable by the program upgrade authority.
+pub fn initialize_stake_operations_config(
+    ctx: Context<InitializeStakeOperationsConfig>,
+) -> Result<()> {
+    validate_program_update_authority(&ctx.accounts.program_data, &ctx.accounts.signer)?;
+
+    let config = &mut ctx.accounts.stake_operations_config;
+    config.deployed_assets = 0;
+    config.total_donated = 0;
+    config.min_reserve_bps = 0;
+    config.hook_program = Pubkey::default();
+    config.require_hook_account = false;
+    config.commit_reveal_enabled = false;
+    config.commit_reveal_delay_slots = 0;
+    config.rate_override_1e9 = None;
+    config.max_single_outflow_bps = 0;
+    config.circuit_breaker_auto_pause = false;
+    config.largest_single_outflow_bps = 0;
+    config.redeem_cooldown_seconds = 0;
+    config.insurance_bps = 0;
+    config.insurance_token_account = Pubkey::default();
+    // A vault already paused when this config is created starts its pause clock now, so the
+    // pause is not treated as expired since the epoch.
+    config.paused_at = if ctx.accounts.stake_config.paused {
+        Clock::get()?.unix_timestamp
+    } else {
+        0
+    };
+    config.max_pause_duration_seconds = MAX_PAUSE_DURATION;
+    config.rounding_dust_accumulated = 0;
+    config.admin_list_version = 0;
+    config.allowed_mint_program = vault_mint::ID;
+    config.max_total_shares = None;
+    config.paused_operations = 0;
+    config.redeem_paused_at = 0;
+    config.events_digest = [0; 32];
+    config.last_totals_update_slot = 0;
+    config.min_supply_for_rewards = 0;
+    config.require_nonempty_freeze_admins = false;
+    config.require_nonempty_rewards_admins = false;
+    config.swap_program = Pubkey::default();
+    config.reward_record_retention = 0;
+    config.external_mint_enabled = true;
+    config.reward_reserve_token_account = Pubkey::default();
+    config.bump = ctx.bumps.stake_operations_config;
+
+    msg!("StakeOperationsConfig initialized");
+    Ok(())
+}
+
+/// Records the amount of vault assets currently deployed outside the vault token account.
+/// The reported figure is added to the vault token balance wherever total assets are used.
+/// Only callable by the program upgrade authority.
+pub fn report_deployed_assets(
+    ctx: Context<UpdateStakeOperationsConfig>,
+    amount: u64,
+) -> Result<()> {
+    validate_program_update_authority(&ctx.accounts.program_data, &ctx.accounts.signer)?;
+
+    let config = &mut ctx.accounts.stake_operations_config;
+    let old_value = config.deployed_assets;
+    config.deployed_assets = amount;
+    record_totals_update(config)?;
+
+    emit_digested(
+        &mut ctx.accounts.stake_operations_config,
+        DeployedAssetsReported {
+            admin: ctx.accounts.signer.key(),
+            old_value,
+            new_value: amount,
+            stake_config: ctx.accounts.stake_config.key(),
+        },
+    );
+
+    msg!("deployed_assets updated: {} -> {}", old_value, amount);
+    Ok(())
+}
+
+/// Get the vault's total assets: the vault token account balance plus deployed assets.
+/// Returns value via return_data for efficient CPI access
+pub fn total_assets(ctx: Context<VaultAssetsView>) -> Result<u64> {
+    let idle_assets = ctx.accounts.vault_token_account.amount;
+    let total_assets = total_vault_assets(idle_assets, &ctx.accounts.stake_operations_config)?;
+
+    msg!(
+        "total_assets: {} (idle: {}, deployed: {})",
+        total_assets,
+        idle_assets,
+        ctx.accounts.stake_operations_config.deployed_assets
+    );
+
+    anchor_lang::solana_program::program::set_return_data(&total_assets.to_le_bytes());
+
+    Ok(total_assets)
+}
+
+/// Projects the assets backing `user_shares` after a hypothetical `reward_amount` is published:
+/// user_shares * (total_assets + reward_amount) / total_shares, rounded down.
+/// Deposit and redeem price at the oracle rate, so this is the pro-rata (NAV) backing that a
+/// reward raises, not the redeem payout; the oracle rate reflects rewards once the feed does.
+/// Returns 0 while no shares are outstanding. Returns value via return_data for efficient CPI
+/// access
+pub fn project_value(
+    ctx: Context<VaultAssetsView>,
+    user_shares: u64,
+    reward_amount: u64,
+) -> Result<u64> {
+    let total_shares = ctx.accounts.mint.supply;
+    let total_assets = total_vault_assets(
+        ctx.accounts.vault_token_account.amount,
+        &ctx.accounts.stake_operations_config,
+    )?;
+    let projected_assets = (total_assets as u128)
+        .checked_add(reward_amount as u128)
+        .ok_or(CustomErrorCode::Overflow)?;
+
+    let projected: u64 = if total_shares == 0 {
+        0
+    } else {
+        (user_shares as u128)
+            .checked_mul(projected_assets)
+            .ok_or(CustomErrorCode::Overflow)?
+            .checked_div(total_shares as u128)
+            .ok_or(CustomErrorCode::DivisionByZero)?
+            .try_into()
+            .map_err(|_| CustomErrorCode::Overflow)?
+    };
+
+    msg!(
+        "project_value: {} shares -> {} assets (total_assets {} + reward {}, total_shares {})",
+        user_shares,
+        projected,
+        total_assets,
+        reward_amount,
+        total_shares
+    );
+
+    anchor_lang::solana_program::program::set_return_data(&projected.to_le_bytes());
+
+    Ok(projected)
+}
+
+/// Get the largest amount of vault tokens that can be withdrawn right now: the idle balance
+/// of the vault token account less the reserve floor (min_reserve_bps). Deployed assets are
+/// excluded because they are not immediately withdrawable.
+/// Returns value via return_data for efficient CPI access
+pub fn available_liquidity(ctx: Context<VaultAssetsView>) -> Result<u64> {
+    let operations_config = &ctx.accounts.stake_operations_config;
+    let available =
+        max_redeemable_assets(ctx.accounts.vault_token_account.amount, operations_config)?;
+
+    msg!(
+        "available_liquidity: {} (deployed, not withdrawable: {}, min_reserve_bps: {})",
+        available,
+        operations_config.deployed_assets,
+        operations_config.min_reserve_bps
+    );
+
+    anchor_lang::solana_program::program::set_return_data(&available.to_le_bytes());
+
+    Ok(available)
+}
+
+/// Returns the rounding dust deposit and redeem have left in the vault, in vault-token base
+/// units scaled by ROUNDING_DUST_SCALE.
+pub fn rounding_dust(ctx: Context<VaultAssetsView>) -> Result<u128> {
+    let accumulated = ctx
+        .accounts
+        .stake_operations_config
+        .rounding_dust_accumulated;
+    msg!(
+        "rounding_dust: {} ({} whole vault-token units)",
+        accumulated,
+        accumulated / ROUNDING_DUST_SCALE
+    );
+
+    anchor_lang::solana_program::program::set_return_data(&accumulated.to_le_bytes());
+
+    Ok(accumulated)
+}
+
+/// Returns events_digest, the rolling hash of every event emitted so far.
+pub fn events_digest(ctx: Context<VaultAssetsView>) -> Result<[u8; 32]> {
+    let digest = ctx.accounts.stake_operations_config.events_digest;
+    msg!("events_digest: {:?}", digest);
+
+    anchor_lang::solana_program::program::set_return_data(&digest);
+
+    Ok(digest)
+}
+
+/// The shares redeem would burn to pay out exactly `assets_out` (ERC4626 previewWithdraw):
+/// ceil(assets_out * price_scale / price), rounded up so redeeming them yields at least
+/// `assets_out`. Uses redeem's price (rate override or stored price, without the staleness check).
+pub fn shares_for_exact_assets(ctx: Context<VaultAssetsView>, assets_out: u64) -> Result<u64> {
+    require!(assets_out > 0, CustomErrorCode::InvalidAmount);
+    let (price, price_scale) = preview_price(
+        &ctx.accounts.stake_operations_config,
+        &ctx.accounts.stake_price_config,
+    )?;
+
+    let shares: u64 = (assets_out as u128)
+        .checked_mul(price_scale as u128)
+        .ok_or(CustomErrorCode::Overflow)?
+        .div_ceil(price as u128)
+        .try_into()
+        .map_err(|_| CustomErrorCode::Overflow)?;
+
+    msg!(
+        "shares_for_exact_assets: {} assets need {} shares",
+        assets_out,
+        shares
+    );
+    anchor_lang::solana_program::program::set_return_data(&shares.to_le_bytes());
+
+    Ok(shares)
+}
+
+/// Collateralization in BPS: total vault assets (vault balance plus deployed assets) over what
+/// the outstanding shares are worth at redeem's price (supply * price / price_scale). 10_000 is
+/// fully collateralized; below it the vault could not pay every holder in full. Returns u64::MAX
+/// when nothing is owed (no shares outstanding).
+pub fn collateralization(ctx: Context<VaultAssetsView>) -> Result<u64> {
+    let (price, price_scale) = preview_price(
+        &ctx.accounts.stake_operations_config,
+        &ctx.accounts.stake_price_config,
+    )?;
+    let total_assets = total_vault_assets(
+        ctx.accounts.vault_token_account.amount,
+        &ctx.accounts.stake_operations_config,
+    )?;
+    let owed = quoted_assets_for_shares(ctx.accounts.mint.supply, price, price_scale)?;
+
+    let ratio_bps = if owed == 0 {
+        u64::MAX
+    } else {
+        // Saturate rather than fail: a vault holding far more than it owes is still healthy.
+        (total_assets as u128)
+            .checked_mul(StakeOperationsConfig::MAX_BPS as u128)
+            .ok_or(CustomErrorCode::Overflow)?
+            .checked_div(owed)
+            .ok_or(CustomErrorCode::DivisionByZero)?
+            .min(u64::MAX as u128) as u64
+    };
+
+    msg!(
+        "collateralization: {} assets / {} owed = {} bps",
+        total_assets,
+        owed,
+        ratio_bps
+    );
+    anchor_lang::solana_program::program::set_return_data(&ratio_bps.to_le_bytes());
+
+    Ok(ratio_bps)
+}
+
+/// The (price, price_scale) deposit and redeem would use, for previews: the rate override when
+/// active, otherwise the stored price. Staleness is not checked.
+fn preview_price(
+    operations_config: &StakeOperationsConfig,
+    price_config: &StakePriceConfig,
+) -> Result<(i128, u64)> {
+    match operations_config.rate_override_1e9 {
+        Some(rate) => Ok((rate as i128, EXCHANGE_RATE_SCALE)),
+        None => {
+            require!(
+                price_config.price > 0 && price_config.price_timestamp > 0,
+                CustomErrorCode::PriceNotInitialized
+            );
+            Ok((price_config.price, price_config.price_scale))
+        }
+    }
+}
+
+/// Dry-runs a deposit of `amount` followed by an immediate redeem of the minted shares, using
+/// the price deposit and redeem would use (rate override or stored price, without the staleness
+/// check) and the insurance cut. Returns the round-trip loss in vault-token base units: the
+/// insurance cut plus rounding, which should stay below one share's worth of vault tokens.
+pub fn simulate_cycle(ctx: Context<VaultAssetsView>, amount: u64) -> Result<u64> {
+    require!(amount > 0, CustomErrorCode::InvalidAmount);
+    let operations_config = &ctx.accounts.stake_operations_config;
+    let (price, price_scale) = preview_price(operations_config, &ctx.accounts.stake_price_config)?;
+
+    // deposit: the insurance cut leaves the vault amount, priced down into shares
+    let insurance_amount = insurance_share(amount, operations_config)?;
+    let vault_amount = amount
+        .checked_sub(insurance_amount)
+        .ok_or(CustomErrorCode::Overflow)?;
+    let shares: u64 = (vault_amount as u128)
+        .checked_mul(price_scale as u128)
+        .ok_or(CustomErrorCode::Overflow)?
+        .checked_div(price as u128)
+        .ok_or(CustomErrorCode::DivisionByZero)?
+        .try_into()
+        .map_err(|_| CustomErrorCode::DepositAmountTooLarge)?;
+
+    // redeem: the shares priced back down into vault tokens
+    let assets_returned: u64 = quoted_assets_for_shares(shares, price, price_scale)?
+        .try_into()
+        .map_err(|_| CustomErrorCode::Overflow)?;
+    let loss = amount
+        .checked_sub(assets_returned)
+        .ok_or(CustomErrorCode::Overflow)?;
+
+    msg!(
+        "simulate_cycle: {} assets -> {} shares -> {} assets (loss {}, insurance {})",
+        amount,
+        shares,
+        assets_returned,
+        loss,
+        insurance_amount
+    );
+    anchor_lang::solana_program::program::set_return_data(&loss.to_le_bytes());
+
+    Ok(loss)
+}
+
+/// Creates the StakeRewardPolicyConfig PDA with no per-admin budgets.
+/// Only callable by the program upgrade authority.
+pub fn initialize_stake_reward_policy_config(
+    ctx: Context<InitializeStakeRewardPolicyConfig>,
+) -> Result<()> {
+    validate_program_update_authority(&ctx.accounts.program_data, &ctx.accounts.signer)?;
+
+    let config = &mut ctx.accounts.stake_reward_policy_config;
+    config.budget_admins = vec![];
+    config.budget_remaining = vec![];
+    config.disabled_admins = vec![];
+    config.reward_record_count = 0;
+    config.enforce_monotonic_ids = false;
+    config.next_expected_reward_id = 0;
+    config.bump = ctx.bumps.stake_reward_policy_config;
+
+    msg!("StakeRewardPolicyConfig initialized");
+    Ok(())
+}
+
+/// Sets (or replenishes) the remaining publication budget of a rewards administrator.
+/// Entries of administrators no longer in rewards_administrators are dropped.
+/// Only callable by the program upgrade authority.
+pub fn set_reward_budget(
+    ctx: Context<UpdateStakeRewardPolicyConfig>,
+    admin: Pubkey,
+    budget: u64,
+) -> Result<()> {
+    validate_program_update_authority(&ctx.accounts.program_data, &ctx.accounts.signer)?;
+
+    let rewards_administrators = &ctx.accounts.stake_config.rewards_administrators;
+    require!(
+        rewards_administrators.contains(&admin),
+        CustomErrorCode::InvalidRewardsAdministrator
+    );
+
+    let config = &mut ctx.accounts.stake_reward_policy_config;
+    let (budget_admins, budget_remaining): (Vec<Pubkey>, Vec<u64>) = config
+        .budget_admins
+        .iter()
+        .zip(config.budget_remaining.iter())
+        .filter(|(a, _)| rewards_administrators.contains(a))
+        .map(|(a, r)| (*a, *r))
+        .unzip();
+    config.budget_admins = budget_admins;
+    config.budget_remaining = budget_remaining;
+
+    let old_budget = match config.budget_admins.iter().position(|a| *a == admin) {
+        Some(index) => {
+            let old = config.budget_remaining[index];
+            config.budget_remaining[index] = budget;
+            Some(old)
+        }
+        None => {
+            config.budget_admins.push(admin);
+            config.budget_remaining.push(budget);
+            None
+        }
+    };
+
+    emit_digested(
+        &mut ctx.accounts.stake_operations_config,
+        RewardBudgetUpdated {
+            admin,
+            old_budget,
+            new_budget: budget,
+            stake_config: ctx.accounts.stake_config.key(),
+        },
+    );
+
+    msg!("reward budget for {} set to {}", admin, budget);
+    Ok(())
+}
+
+/// Disables or re-enables a single rewards administrator without touching
+/// rewards_administrators. Entries of administrators no longer in the list are dropped.
+/// Only callable by the program upgrade authority.
+pub fn set_rewards_admin_enabled(
+    ctx: Context<UpdateStakeRewardPolicyConfig>,
+    admin: Pubkey,
+    enabled: bool,
+) -> Result<()> {
+    validate_program_update_authority(&ctx.accounts.program_data, &ctx.accounts.signer)?;
+
+    let rewards_administrators = &ctx.accounts.stake_config.rewards_administrators;
+    require!(
+        rewards_administrators.contains(&admin),
+        CustomErrorCode::InvalidRewardsAdministrator
+    );
+
+    let config = &mut ctx.accounts.stake_reward_policy_config;
+    config
+        .disabled_admins
+        .retain(|a| *a != admin && rewards_administrators.contains(a));
+    if !enabled {
+        config.disabled_admins.push(admin);
+    }
+
+    emit_digested(
+        &mut ctx.accounts.stake_operations_config,
+        RewardsAdminEnabledUpdated {
+            admin,
+            enabled,
+            stake_config: ctx.accounts.stake_config.key(),
+        },
+    );
+
+    msg!("rewards administrator {} enabled: {}", admin, enabled);
+    Ok(())
+}
+
+/// Turns strictly increasing reward ids on or off for publish_rewards and publish_epoch_marker.
+/// next_expected_reward_id keeps tracking published ids either way.
+/// Only callable by the program upgrade authority.
+pub fn set_reward_id_enforcement(
+    ctx: Context<UpdateStakeRewardPolicyConfig>,
+    enforce_monotonic_ids: bool,
+) -> Result<()> {
+    validate_program_update_authority(&ctx.accounts.program_data, &ctx.accounts.signer)?;
+
+    let config = &mut ctx.accounts.stake_reward_policy_config;
+    config.enforce_monotonic_ids = enforce_monotonic_ids;
+
+    emit_digested(
+        &mut ctx.accounts.stake_operations_config,
+        RewardIdEnforcementUpdated {
+            enforce_monotonic_ids,
+            next_expected_reward_id: config.next_expected_reward_id,
+            stake_config: ctx.accounts.stake_config.key(),
+        },
+    );
+
+    msg!(
+        "enforce_monotonic_ids: {} (next expected id {})",
+        enforce_monotonic_ids,
+        config.next_expected_reward_id
+    );
+    Ok(())
+}
+
+/// Get the number of reward records published so far (reward publications and epoch markers).
+/// Returns value via return_data for efficient CPI access
+pub fn reward_record_count(ctx: Context<RewardPolicyView>) -> Result<u64> {
+    let count = ctx.accounts.stake_reward_policy_config.reward_record_count;
+    msg!("reward_record_count: {}", count);
+    Ok(count)
+}
+
+/// Sets the share of total assets (BPS) that must remain idle in the vault token account after
+/// a redeem. 0 disables the reserve floor.
+/// Only callable by the program upgrade authority.
+pub fn update_min_reserve_bps(
+    ctx: Context<UpdateStakeOperationsConfig>,
+    min_reserve_bps: u16,
+) -> Result<()> {
+    validate_program_update_authority(&ctx.accounts.program_data, &ctx.accounts.signer)?;
+    require!(
+        min_reserve_bps <= StakeOperationsConfig::MAX_BPS,
+        CustomErrorCode::InvalidMinReserveBps
+    );
+
+    let config = &mut ctx.accounts.stake_operations_config;
+    let old_bps = config.min_reserve_bps;
+    config.min_reserve_bps = min_reserve_bps;
+
+    emit_digested(
+        &mut ctx.accounts.stake_operations_config,
+        MinReserveBpsUpdated {
+            admin: ctx.accounts.signer.key(),
+            old_bps,
+            new_bps: min_reserve_bps,
+            stake_config: ctx.accounts.stake_config.key(),
+        },
+    );
+
+    msg!(
+        "min_reserve_bps updated: {} -> {}",
+        old_bps,
+        min_reserve_bps
+    );
+    Ok(())
+}
+
+/// Sets the minimum number of seconds between a user's consecutive redeems. A partial redeem
+/// restarts the cooldown just like a full one. 0 disables the cooldown.
+/// Only callable by the program upgrade authority.
+pub fn update_redeem_cooldown(
+    ctx: Context<UpdateStakeOperationsConfig>,
+    redeem_cooldown_seconds: i64,
+) -> Result<()> {
+    validate_program_update_authority(&ctx.accounts.program_data, &ctx.accounts.signer)?;
+    require!(
+        redeem_cooldown_seconds >= 0,
+        CustomErrorCode::InvalidRedeemCooldown
+    );
+
+    let config = &mut ctx.accounts.stake_operations_config;
+    let old_seconds = config.redeem_cooldown_seconds;
+    config.redeem_cooldown_seconds = redeem_cooldown_seconds;
+
+    emit_digested(
+        &mut ctx.accounts.stake_operations_config,
+        RedeemCooldownUpdated {
+            admin: ctx.accounts.signer.key(),
+            old_seconds,
+            new_seconds: redeem_cooldown_seconds,
+            stake_config: ctx.accounts.stake_config.key(),
+        },
+    );
+
+    msg!(
+        "redeem_cooldown_seconds updated: {} -> {}",
+        old_seconds,
+        redeem_cooldown_seconds
+    );
+    Ok(())
+}
+
+/// Sets the vault-mint program publish_rewards accepts as mint_program.
+/// Only callable by the program upgrade authority.
+pub fn update_allowed_mint_program(
+    ctx: Context<UpdateStakeOperationsConfig>,
+    mint_program: Pubkey,
+) -> Result<()> {
+    validate_program_update_authority(&ctx.accounts.program_data, &ctx.accounts.signer)?;
+    require_keys_neq!(
+        mint_program,
+        Pubkey::default(),
+        CustomErrorCode::InvalidMintProgram
+    );
+
+    let config = &mut ctx.accounts.stake_operations_config;
+    let old_mint_program = config.allowed_mint_program;
+    config.allowed_mint_program = mint_program;
+
+    emit_digested(
+        &mut ctx.accounts.stake_operations_config,
+        AllowedMintProgramUpdated {
+            admin: ctx.accounts.signer.key(),
+            old_mint_program,
+            new_mint_program: mint_program,
+            stake_config: ctx.accounts.stake_config.key(),
+        },
+    );
+
+    msg!(
+        "allowed_mint_program updated: {} -> {}",
+        old_mint_program,
+        mint_program
+    );
+    Ok(())
+}
+
+/// Sets (Some) or removes (None) the cap on the stake mint's supply enforced by deposit.
+/// Only callable by the program upgrade authority.
+pub fn update_max_total_shares(
+    ctx: Context<UpdateStakeOperationsConfig>,
+    max_total_shares: Option<u64>,
+) -> Result<()> {
+    validate_program_update_authority(&ctx.accounts.program_data, &ctx.accounts.signer)?;
+
+    let config = &mut ctx.accounts.stake_operations_config;
+    let old_max_total_shares = config.max_total_shares;
+    config.max_total_shares = max_total_shares;
+
+    emit_digested(
+        &mut ctx.accounts.stake_operations_config,
+        MaxTotalSharesUpdated {
+            admin: ctx.accounts.signer.key(),
+            old_max_total_shares,
+            new_max_total_shares: max_total_shares,
+            stake_config: ctx.accounts.stake_config.key(),
+        },
+    );
+
+    msg!(
+        "max_total_shares updated: {:?} -> {:?}",
+        old_max_total_shares,
+        max_total_shares
+    );
+    Ok(())
+}
+
+/// Sets how many seconds reward records are kept before publish_rewards may prune them; 0 keeps
+/// them all. Only callable by the program upgrade authority.
+pub fn update_reward_record_retention(
+    ctx: Context<UpdateStakeOperationsConfig>,
+    reward_record_retention: i64,
+) -> Result<()> {
+    validate_program_update_authority(&ctx.accounts.program_data, &ctx.accounts.signer)?;
+    require!(
+        reward_record_retention >= 0,
+        CustomErrorCode::InvalidRewardRecordRetention
+    );
+
+    let config = &mut ctx.accounts.stake_operations_config;
+    let old_reward_record_retention = config.reward_record_retention;
+    config.reward_record_retention = reward_record_retention;
+
+    emit_digested(
+        &mut ctx.accounts.stake_operations_config,
+        RewardRecordRetentionUpdated {
+            admin: ctx.accounts.signer.key(),
+            old_reward_record_retention,
+            new_reward_record_retention: reward_record_retention,
+            stake_config: ctx.accounts.stake_config.key(),
+        },
+    );
+
+    msg!(
+        "reward_record_retention updated: {} -> {}",
+        old_reward_record_retention,
+        reward_record_retention
+    );
+    Ok(())
+}
+
+/// Enables or disables publish_rewards' CPI into vault-mint. While disabled, rewards can only be
+/// paid through publish_rewards_from_reserve. Only callable by the program upgrade authority.
+pub fn update_external_mint_enabled(
+    ctx: Context<UpdateStakeOperationsConfig>,
+    enabled: bool,
+) -> Result<()> {
+    validate_program_update_authority(&ctx.accounts.program_data, &ctx.accounts.signer)?;
+
+    let config = &mut ctx.accounts.stake_operations_config;
+    config.external_mint_enabled = enabled;
+
+    emit_digested(
+        &mut ctx.accounts.stake_operations_config,
+        ExternalMintEnabledUpdated {
+            admin: ctx.accounts.signer.key(),
+            enabled,
+            stake_config: ctx.accounts.stake_config.key(),
+        },
+    );
+
+    msg!("external_mint_enabled updated: {}", enabled);
+    Ok(())
+}
+
+/// Sets the vault-token account publish_rewards_from_reserve pays rewards from.
+/// Only callable by the program upgrade authority.
+pub fn update_reward_reserve(ctx: Context<UpdateRewardReserve>) -> Result<()> {
+    validate_program_update_authority(&ctx.accounts.program_data, &ctx.accounts.signer)?;
+
+    let config = &mut ctx.accounts.stake_operations_config;
+    let old_reward_reserve_token_account = config.reward_reserve_token_account;
+    config.reward_reserve_token_account = ctx.accounts.reward_reserve_token_account.key();
+
+    emit_digested(
+        &mut ctx.accounts.stake_operations_config,
+        RewardReserveUpdated {
+            admin: ctx.accounts.signer.key(),
+            old_reward_reserve_token_account,
+            new_reward_reserve_token_account: ctx.accounts.reward_reserve_token_account.key(),
+            stake_config: ctx.accounts.stake_config.key(),
+        },
+    );
+
+    msg!(
+        "reward_reserve_token_account updated: {} -> {}",
+        old_reward_reserve_token_account,
+        ctx.accounts.reward_reserve_token_account.key()
+    );
+    Ok(())
+}
+
+/// Sets the program zap_deposit swaps through (Pubkey::default() disables zaps).
+/// Only callable by the program upgrade authority.
+pub fn update_swap_program(
+    ctx: Context<UpdateStakeOperationsConfig>,
+    swap_program: Pubkey,
+) -> Result<()> {
+    validate_program_update_authority(&ctx.accounts.program_data, &ctx.accounts.signer)?;
+
+    let config = &mut ctx.accounts.stake_operations_config;
+    let old_swap_program = config.swap_program;
+    config.swap_program = swap_program;
+
+    emit_digested(
+        &mut ctx.accounts.stake_operations_config,
+        SwapProgramUpdated {
+            admin: ctx.accounts.signer.key(),
+            old_swap_program,
+            new_swap_program: swap_program,
+            stake_config: ctx.accounts.stake_config.key(),
+        },
+    );
+
+    msg!(
+        "swap program updated: {} -> {}",
+        old_swap_program,
+        swap_program
+    );
+    Ok(())
+}
+
+/// Sets whether the freeze and rewards administrator lists may be updated to empty.
+/// Only callable by the program upgrade authority.
+pub fn update_nonempty_admin_requirements(
+    ctx: Context<UpdateStakeOperationsConfig>,
+    require_nonempty_freeze_admins: bool,
+    require_nonempty_rewards_admins: bool,
+) -> Result<()> {
+    validate_program_update_authority(&ctx.accounts.program_data, &ctx.accounts.signer)?;
+
+    let config = &mut ctx.accounts.stake_operations_config;
+    config.require_nonempty_freeze_admins = require_nonempty_freeze_admins;
+    config.require_nonempty_rewards_admins = require_nonempty_rewards_admins;
+
+    emit_digested(
+        &mut ctx.accounts.stake_operations_config,
+        NonemptyAdminRequirementsUpdated {
+            admin: ctx.accounts.signer.key(),
+            require_nonempty_freeze_admins,
+            require_nonempty_rewards_admins,
+            stake_config: ctx.accounts.stake_config.key(),
+        },
+    );
+
+    msg!(
+        "nonempty admin requirements: freeze {}, rewards {}",
+        require_nonempty_freeze_admins,
+        require_nonempty_rewards_admins
+    );
+    Ok(())
+}
+
+/// Sets the minimum stake mint supply publish_rewards requires; 0 disables the check.
+/// Only callable by the program upgrade authority.
+pub fn update_min_supply_for_rewards(
+    ctx: Context<UpdateStakeOperationsConfig>,
+    min_supply_for_rewards: u64,
+) -> Result<()> {
+    validate_program_update_authority(&ctx.accounts.program_data, &ctx.accounts.signer)?;
+
+    let config = &mut ctx.accounts.stake_operations_config;
+    let old_min_supply_for_rewards = config.min_supply_for_rewards;
+    config.min_supply_for_rewards = min_supply_for_rewards;
+
+    emit_digested(
+        &mut ctx.accounts.stake_operations_config,
+        MinSupplyForRewardsUpdated {
+            admin: ctx.accounts.signer.key(),
+            old_min_supply_for_rewards,
+            new_min_supply_for_rewards: min_supply_for_rewards,
+            stake_config: ctx.accounts.stake_config.key(),
+        },
+    );
+
+    msg!(
+        "min_supply_for_rewards updated: {} -> {}",
+        old_min_supply_for_rewards,
+        min_supply_for_rewards
+    );
+    Ok(())
+}
+
+/// Sets how long a pause may block redeem, in seconds (1..=MAX_PAUSE_DURATION).
+/// Only callable by the program upgrade authority.
+pub fn update_max_pause_duration(
+    ctx: Context<UpdateStakeOperationsConfig>,
+    max_pause_duration_seconds: i64,
+) -> Result<()> {
+    validate_program_update_authority(&ctx.accounts.program_data, &ctx.accounts.signer)?;
+    require!(
+        max_pause_duration_seconds > 0 && max_pause_duration_seconds <= MAX_PAUSE_DURATION,
+        CustomErrorCode::InvalidMaxPauseDuration
+    );
+
+    let config = &mut ctx.accounts.stake_operations_config;
+    let old_seconds = config.max_pause_duration_seconds;
+    config.max_pause_duration_seconds = max_pause_duration_seconds;
+
+    emit_digested(
+        &mut ctx.accounts.stake_operations_config,
+        MaxPauseDurationUpdated {
+            admin: ctx.accounts.signer.key(),
+            old_seconds,
+            new_seconds: max_pause_duration_seconds,
+            stake_config: ctx.accounts.stake_config.key(),
+        },
+    );
+
+    msg!(
+        "max_pause_duration_seconds updated: {} -> {}",
+        old_seconds,
+        max_pause_duration_seconds
+    );
+    Ok(())
+}
+
+/// Sets the share of each deposit (BPS) routed to the insurance fund and the vault-token account
+/// that holds it. 0 disables the split. Only callable by the program upgrade authority.
+pub fn update_insurance_config(
+    ctx: Context<UpdateInsuranceConfig>,
+    insurance_bps: u16,
+) -> Result<()> {
+    validate_program_update_authority(&ctx.accounts.program_data, &ctx.accounts.signer)?;
+    require!(
+        insurance_bps < StakeOperationsConfig::MAX_BPS,
+        CustomErrorCode::InvalidInsuranceBps
+    );
+
+    let config = &mut ctx.accounts.stake_operations_config;
+    let old_bps = config.insurance_bps;
+    config.insurance_bps = insurance_bps;
+    config.insurance_token_account = ctx.accounts.insurance_token_account.key();
+
+    emit_digested(
+        &mut ctx.accounts.stake_operations_config,
+        InsuranceConfigUpdated {
+            admin: ctx.accounts.signer.key(),
+            old_bps,
+            new_bps: insurance_bps,
+            insurance_token_account: ctx.accounts.insurance_token_account.key(),
+            stake_config: ctx.accounts.stake_config.key(),
+        },
+    );
+
+    msg!(
+        "insurance_bps updated: {} -> {} (account {})",
+        old_bps,
+        insurance_bps,
+        ctx.accounts.insurance_token_account.key()
+    );
+    Ok(())
+}
+
+/// Moves `amount` from the insurance fund into the vault token account to cover a shortfall.
+/// The vault's total assets grow by `amount` without minting shares.
+/// Only callable by the program upgrade authority.
+pub fn draw_insurance(ctx: Context<DrawInsurance>, amount: u64) -> Result<()> {
+    validate_program_update_authority(&ctx.accounts.program_data, &ctx.accounts.signer)?;
+    require!(amount > 0, CustomErrorCode::InvalidAmount);
+    require!(
+        ctx.accounts.insurance_token_account.amount >= amount,
+        CustomErrorCode::InsufficientInsuranceFunds
+    );
+
+    let seeds: &[&[u8]] = &[b"vault_authority", &[ctx.bumps.vault_authority]];
+    let signer = &[&seeds[..]];
+    let transfer_accounts = Transfer {
+        from: ctx.accounts.insurance_token_account.to_account_info(),
+        to: ctx.accounts.vault_token_account.to_account_info(),
+        authority: ctx.accounts.vault_authority.to_account_info(),
+    };
+    token::transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            transfer_accounts,
+            signer,
+        ),
+        amount,
+    )?;
+
+    ctx.accounts.insurance_token_account.reload()?;
+    ctx.accounts.vault_token_account.reload()?;
+    record_totals_update(&mut ctx.accounts.stake_operations_config)?;
+    emit_digested(
+        &mut ctx.accounts.stake_operations_config,
+        InsuranceDrawn {
+            admin: ctx.accounts.signer.key(),
+            amount,
+            insurance_balance: ctx.accounts.insurance_token_account.amount,
+            vault_balance: ctx.accounts.vault_token_account.amount,
+            stake_config: ctx.accounts.stake_config.key(),
+        },
+    );
+
+    msg!("Drew {} from the insurance fund into the vault", amount);
+    Ok(())
+}
+
+/// Sets the program called after every deposit and redeem (Pubkey::default() removes it) and
+/// whether deposit/redeem must fail when the hook program account is not passed. A failing hook
+/// reverts either way.
+/// Only callable by the program upgrade authority.
+pub fn update_hook(
+    ctx: Context<UpdateStakeOperationsConfig>,
+    hook_program: Pubkey,
+    require_hook_account: bool,
+) -> Result<()> {
+    validate_program_update_authority(&ctx.accounts.program_data, &ctx.accounts.signer)?;
+
+    let config = &mut ctx.accounts.stake_operations_config;
+    let old_hook_program = config.hook_program;
+    config.hook_program = hook_program;
+    config.require_hook_account = require_hook_account;
+
+    emit_digested(
+        &mut ctx.accounts.stake_operations_config,
+        HookUpdated {
+            admin: ctx.accounts.signer.key(),
+            old_hook_program,
+            new_hook_program: hook_program,
+            require_hook_account,
+            stake_config: ctx.accounts.stake_config.key(),
+        },
+    );
+
+    msg!(
+        "hook updated: {} -> {} (account required: {})",
+        old_hook_program,
+        hook_program,
+        require_hook_account
+    );
+    Ok(())
+}
+
+/// Enables or disables commit-reveal deposits and sets the minimum number of slots between
+/// commit_deposit and reveal_deposit. Only callable by the program upgrade authority.
+pub fn update_commit_reveal(
+    ctx: Context<UpdateStakeOperationsConfig>,
+    enabled: bool,
+    delay_slots: u64,
+) -> Result<()> {
+    validate_program_update_authority(&ctx.accounts.program_data, &ctx.accounts.signer)?;
+
+    let config = &mut ctx.accounts.stake_operations_config;
+    config.commit_reveal_enabled = enabled;
+    config.commit_reveal_delay_slots = delay_slots;
+
+    emit_digested(
+        &mut ctx.accounts.stake_operations_config,
+        CommitRevealUpdated {
+            admin: ctx.accounts.signer.key(),
+            enabled,
+            delay_slots,
+            stake_config: ctx.accounts.stake_config.key(),
+        },
+    );
+
+    msg!(
+        "commit-reveal deposits enabled: {} (delay: {} slots)",
+        enabled,
+        delay_slots
+    );
+    Ok(())
+}
+
+/// Break-glass migration tool: pins the rate deposit and redeem use (wYLDS per PRIME, scaled by
+/// 1e9), bypassing the oracle price. Pass None to clear the override and resume oracle pricing.
+/// Only callable by the program upgrade authority.
+pub fn set_rate_override(
+    ctx: Context<UpdateStakeOperationsConfig>,
+    rate_1e9: Option<u64>,
+) -> Result<()> {
+    validate_program_update_authority(&ctx.accounts.program_data, &ctx.accounts.signer)?;
+    if let Some(rate) = rate_1e9 {
+        require!(rate > 0, CustomErrorCode::InvalidRateOverride);
+    }
+
+    let config = &mut ctx.accounts.stake_operations_config;
+    let old_rate_1e9 = config.rate_override_1e9;
+    config.rate_override_1e9 = rate_1e9;
+
+    emit_digested(
+        &mut ctx.accounts.stake_operations_config,
+        RateOverrideUpdated {
+            admin: ctx.accounts.signer.key(),
+            old_rate_1e9,
+            new_rate_1e9: rate_1e9,
+            active: rate_1e9.is_some(),
+            stake_config: ctx.accounts.stake_config.key(),
+        },
+    );
+
+    msg!("rate override: {:?} -> {:?}", old_rate_1e9, rate_1e9);
+    Ok(())
+}
+
+/// Configures the redeem circuit breaker: the largest share of total assets (BPS) a single
+/// redeem may withdraw (0 disables it) and whether exceeding it pauses the protocol (true) or
+/// just fails the redeem (false). Only callable by the program upgrade authority.
+pub fn update_circuit_breaker(
+    ctx: Context<UpdateStakeOperationsConfig>,
+    max_single_outflow_bps: u16,
+    auto_pause: bool,
+) -> Result<()> {
+    validate_program_update_authority(&ctx.accounts.program_data, &ctx.accounts.signer)?;
+    require!(
+        max_single_outflow_bps <= StakeOperationsConfig::MAX_BPS,
+        CustomErrorCode::InvalidMaxSingleOutflowBps
+    );
+
+    let config = &mut ctx.accounts.stake_operations_config;
+    let old_max_single_outflow_bps = config.max_single_outflow_bps;
+    config.max_single_outflow_bps = max_single_outflow_bps;
+    config.circuit_breaker_auto_pause = auto_pause;
+
+    emit_digested(
+        &mut ctx.accounts.stake_operations_config,
+        CircuitBreakerUpdated {
+            admin: ctx.accounts.signer.key(),
+            old_max_single_outflow_bps,
+            new_max_single_outflow_bps: max_single_outflow_bps,
+            auto_pause,
+            stake_config: ctx.accounts.stake_config.key(),
+        },
+    );
+
+    msg!(
+        "circuit breaker: {} -> {} bps (auto-pause: {})",
+        old_max_single_outflow_bps,
+        max_single_outflow_bps,
+        auto_pause
+    );
+    Ok(())
+}
+
+/// `part` as a share of `total` in BPS, capped at MAX_BPS. An empty total counts as 0 BPS.
+fn bps_of(part: u64, total: u64) -> Result<u16> {
+    if total == 0 {
+        return Ok(0);
+    }
+    let bps = (part as u128)
+        .checked_mul(StakeOperationsConfig::MAX_BPS as u128)
+        .ok_or(CustomErrorCode::Overflow)?
+        / total as u128;
+    Ok(bps.min(StakeOperationsConfig::MAX_BPS as u128) as u16)
+}
+
+/// True once a pause that started at `paused_at` (paused_at or redeem_paused_at) is older than
+/// max_pause_duration_seconds, after which redeem ignores it. Deposits and reward publication
+/// stay paused. A pause with no recorded start (0) never expires.
+fn pause_expired(paused_at: i64, operations_config: &StakeOperationsConfig) -> Result<bool> {
+    if paused_at == 0 {
+        return Ok(false);
+    }
+    let expires_at = paused_at
+        .checked_add(operations_config.max_pause_duration_seconds)
+        .ok_or(CustomErrorCode::Overflow)?;
+    Ok(Clock::get()?.unix_timestamp > expires_at)
+}
+
+/// Fails with OperationPaused while `operation` (a PAUSE_* bit) is set in paused_operations.
+fn require_operation_active(
+    operations_config: &StakeOperationsConfig,
+    operation: u8,
+) -> Result<()> {
+    require!(
+        operations_config.paused_operations & operation == 0,
+        CustomErrorCode::OperationPaused
+    );
+    Ok(())
+}
+
+/// Walks `proof` from `leaf` up to a root, vault-mint style: a zero sibling hashes the node alone,
+/// otherwise is_left orders the pair.
+fn merkle_root_from_proof(leaf: [u8; 32], proof: &[ProofNode]) -> [u8; 32] {
+    proof.iter().fold(leaf, |node, step| {
+        if step.sibling.iter().all(|&b| b == 0) {
+            hashv(&[node.as_ref()]).to_bytes()
+        } else if step.is_left {
+            hashv(&[step.sibling.as_ref(), node.as_ref()]).to_bytes()
+        } else {
+            hashv(&[node.as_ref(), step.sibling.as_ref()]).to_bytes()
+        }
+    })
+}
+
+/// Shares of a vesting schedule vested at `now`: total_shares * elapsed / duration_seconds, capped
+/// at total_shares.
+fn vested_shares(vesting: &VestingAccount, now: i64) -> Result<u64> {
+    let elapsed = now
+        .saturating_sub(vesting.start_ts)
+        .clamp(0, vesting.duration_seconds);
+    let vested = (vesting.total_shares as u128)
+        .checked_mul(elapsed as u128)
+        .ok_or(CustomErrorCode::Overflow)?
+        .checked_div(vesting.duration_seconds as u128)
+        .ok_or(CustomErrorCode::DivisionByZero)?;
+    vested
+        .try_into()
+        .map_err(|_| CustomErrorCode::Overflow.into())
+}
+
+/// Checks `id` against next_expected_reward_id when monotonic ids are enforced, then records it.
+/// Reward ids share one namespace across publish_rewards and publish_epoch_marker.
+fn advance_reward_id(policy: &mut StakeRewardPolicyConfig, id: u32) -> Result<()> {
+    let id = id as u64;
+    if policy.enforce_monotonic_ids {
+        require!(
+            policy.next_expected_reward_id <= u32::MAX as u64,
+            CustomErrorCode::RewardIdSpaceExhausted
+        );
+        require!(
+            id >= policy.next_expected_reward_id,
+            CustomErrorCode::RewardIdNotMonotonic
+        );
+    }
+    policy.next_expected_reward_id = policy.next_expected_reward_id.max(id + 1);
+    Ok(())
+}
+
+/// Part of a deposit routed to the insurance fund: floor(amount * insurance_bps / MAX_BPS).
+fn insurance_share(amount: u64, operations_config: &StakeOperationsConfig) -> Result<u64> {
+    let share = (amount as u128)
+        .checked_mul(operations_config.insurance_bps as u128)
+        .ok_or(CustomErrorCode::Overflow)?
+        / StakeOperationsConfig::MAX_BPS as u128;
+    Ok(share as u64)
+}
+
+/// Adds the value of a floored remainder, remainder / price_scale vault tokens, to
+/// rounding_dust_accumulated in ROUNDING_DUST_SCALE fixed point.
+fn accrue_rounding_dust(
+    operations_config: &mut StakeOperationsConfig,
+    remainder: u128,
+    price_scale: u64,
+) -> Result<()> {
+    let dust = remainder
+        .checked_mul(ROUNDING_DUST_SCALE)
+        .ok_or(CustomErrorCode::Overflow)?
+        .checked_div(price_scale as u128)
+        .ok_or(CustomErrorCode::DivisionByZero)?;
+    operations_config.rounding_dust_accumulated = operations_config
+        .rounding_dust_accumulated
+        .checked_add(dust)
+        .ok_or(CustomErrorCode::Overflow)?;
+    Ok(())
+}
+
+/// Assets quoted for `shares` by the shares_to_assets view: shares * price / price_scale.
+fn quoted_assets_for_shares(shares: u64, price: i128, price_scale: u64) -> Result<u128> {
+    Ok((shares as u128)
+        .checked_mul(price as u128)
+        .ok_or(CustomErrorCode::Overflow)?
+        .checked_div(price_scale as u128)
+        .ok_or(CustomErrorCode::DivisionByZero)?)
+}
+
+/// Post-condition of redeem: the assets transferred must match what the burned shares are quoted
+/// at (shares_to_assets) within one unit of rounding. Catches a redeem formula drifting away
+/// from the quoted conversion.
+fn check_redeem_consistency(
+    shares_burned: u64,
+    assets_transferred: u64,
+    price: i128,
+    price_scale: u64,
+) -> Result<()> {
+    let expected = quoted_assets_for_shares(shares_burned, price, price_scale)?;
+    require!(
+        expected.abs_diff(assets_transferred as u128) <= 1,
+        CustomErrorCode::RedeemMathInconsistent
+    );
+    Ok(())
+}
+
+/// Total assets backing the vault: the idle vault token balance plus any assets
+/// reported as deployed outside the vault token account.
+fn total_vault_assets(
+    vault_balance: u64,
+    operations_config: &StakeOperationsConfig,
+) -> Result<u64> {
+    Ok(vault_balance
+        .checked_add(operations_config.deployed_assets)
+        .ok_or(CustomErrorCode::Overflow)?)
+}
+
+/// Largest redeem (in vault tokens) that keeps the idle balance at or above the reserve floor:
+/// the largest `r` with `(idle - r) * MAX_BPS >= (total - r) * min_reserve_bps`, capped at idle.
+fn max_redeemable_assets(
+    vault_balance: u64,
+    operations_config: &StakeOperationsConfig,
+) -> Result<u64> {
+    let reserve_bps = operations_config.min_reserve_bps as u128;
+    if reserve_bps == 0 {
+        return Ok(vault_balance);
+    }
+    let max_bps = StakeOperationsConfig::MAX_BPS as u128;
+    let idle = vault_balance as u128;
+    let total = total_vault_assets(vault_balance, operations_config)? as u128;
+
+    let idle_scaled = idle.checked_mul(max_bps).ok_or(CustomErrorCode::Overflow)?;
+    let reserve_scaled = total
+        .checked_mul(reserve_bps)
+        .ok_or(CustomErrorCode::Overflow)?;
+    if idle_scaled < reserve_scaled {
+        return Ok(0);
+    }
+    if reserve_bps == max_bps {
+        // Everything must stay idle, which only holds when nothing is deployed.
+        return Ok(vault_balance);
+    }
+    let max_redeem = (idle_scaled - reserve_scaled) / (max_bps - reserve_bps);
+    Ok(max_redeem.min(idle) as u64)
+}
+
+/// CPIs into the configured hook program after a successful deposit or redeem.
+/// remaining_accounts[0] must be the hook program; the rest are passed to it without signer
+/// privileges. If the hook program is not passed the hook is skipped, unless require_hook_account
+/// is set. A hook that fails always reverts the transaction, in either mode: the runtime aborts
+/// the caller on a failed CPI, so its result cannot be caught and ignored.
+fn invoke_hook<'info>(
+    operations_config: &StakeOperationsConfig,
+    remaining_accounts: &[AccountInfo<'info>],
+    payload: StakeHookPayload,
+) -> Result<()> {
+    if operations_config.hook_program == Pubkey::default() {
+        return Ok(());
+    }
+    let (hook_program, hook_accounts) = match remaining_accounts.split_first() {
+        Some((program, accounts)) if program.key() == operations_config.hook_program => {
+            (program, accounts)
+        }
+        _ => {
+            require!(
+                !operations_config.require_hook_account,
+                CustomErrorCode::HookProgramMissing
+            );
+            msg!("Hook program not passed; skipping hook");
+            return Ok(());
+        }
+    };
+
+    let mut data = STAKE_HOOK_DISCRIMINATOR.to_vec();
+    payload.serialize(&mut data)?;
+    let instruction = Instruction {
+        program_id: hook_program.key(),
+        accounts: hook_accounts
+            .iter()
+            .map(|account| AccountMeta {
+                pubkey: account.key(),
+                is_signer: false,
+                is_writable: account.is_writable,
+            })
+            .collect(),
+        data,
+    };
+
+    msg!("Invoking hook program {}", hook_program.key());
+    let mut account_infos = hook_accounts.to_vec();
+    account_infos.push(hook_program.clone());
+    invoke(&instruction, &account_infos)?;
+    Ok(())
+}
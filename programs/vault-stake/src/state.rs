@@ -1,6 +1,53 @@
 use anchor_lang::prelude::*;
 
 pub const MAX_ADMINISTRATORS: usize = 5; // max number of freeze/rewards administrators
+pub const EXCHANGE_RATE_SCALE: u64 = 1_000_000_000; // exchange_rate views are scaled by 1e9
+pub const MAX_FREEZE_STATUS_ACCOUNTS: usize = 64; // one bit each in freeze_status_many's u64 mask
+pub const MAX_RESTRICTION_REASON_LEN: usize = 64; // bytes of RestrictionRecord.reason
+pub const MAX_VAULT_OWNERSHIP_ACCOUNTS: usize = 16; // verify_vault_ownership entries fit return data
+pub const PRICE_FEED_CONFIDENCE: u64 = 0; // price_feed conf; Chainlink reports carry no interval
+
+// Hard cap on StakeOperationsConfig.max_pause_duration_seconds (30 days). User protection: once a
+// pause is older than the configured duration, redeem ignores it, so holders can always exit even
+// if every freeze administrator is gone. Admins can shorten the window but never extend it.
+pub const MAX_PAUSE_DURATION: i64 = 30 * 24 * 60 * 60;
+
+// Fixed-point scale of StakeOperationsConfig.rounding_dust_accumulated: ROUNDING_DUST_SCALE equals
+// one vault-token base unit.
+pub const ROUNDING_DUST_SCALE: u128 = 1_000_000_000_000_000_000;
+
+// health_check status bits; a healthy vault returns 0.
+pub const HEALTH_PAUSED: u32 = 1 << 0; // stake_config.paused is set
+pub const HEALTH_VAULT_NOT_PROGRAM_OWNED: u32 = 1 << 1; // vault token account not owned by vault_authority
+pub const HEALTH_PRICE_NOT_INITIALIZED: u32 = 1 << 2; // no oracle price stored yet
+pub const HEALTH_PRICE_STALE: u32 = 1 << 3; // oracle price older than price_max_staleness
+pub const HEALTH_RATE_OVERRIDE_ACTIVE: u32 = 1 << 4; // deposit/redeem priced from rate_override_1e9
+pub const HEALTH_UNDERCOLLATERALIZED: u32 = 1 << 5; // total assets below the supply's value
+pub const HEALTH_SOLVENCY_TOLERANCE_BPS: u64 = 10; // shortfall (of the supply's value) still healthy
+
+// StakeOperationsConfig.paused_operations bits, set by freeze administrators with
+// set_paused_operations. They pause single operations; stake_config.paused still pauses them all.
+// The comment above each bit lists every instruction it makes fail with OperationPaused.
+
+// deposit, deposit_with_ata, deposit_with_relayer, deposit_vesting, zap_deposit, reveal_deposit
+// (commit_deposit stays open; the reveal is what mints)
+pub const PAUSE_DEPOSIT: u8 = 1 << 0;
+// none: reserved since unbond was removed
+pub const PAUSE_UNBOND: u8 = 1 << 1;
+// redeem (expires like paused_at, from redeem_paused_at)
+pub const PAUSE_REDEEM: u8 = 1 << 2;
+// publish_rewards, publish_rewards_from_reserve, publish_stake_reward, publish_epoch_marker
+pub const PAUSE_PUBLISH_REWARDS: u8 = 1 << 3;
+// freeze_token_account, restrict_account (thaw_token_account and lift_restriction stay open)
+pub const PAUSE_FREEZE: u8 = 1 << 4;
+pub const PAUSE_ALL_OPERATIONS: u8 =
+    PAUSE_DEPOSIT | PAUSE_UNBOND | PAUSE_REDEEM | PAUSE_PUBLISH_REWARDS | PAUSE_FREEZE;
+
+// PDA seed scheme reported by the seed_scheme view. Bump whenever any PDA's seeds change.
+// 1: singleton stake_config ([b"stake_config"]) and global authorities ([b"mint_authority"],
+//    [b"vault_authority"], [b"freeze_authority"]); every other PDA is keyed by the stake_config
+//    address, plus the user/token account where it is per-user.
+pub const SEED_SCHEME_VERSION: u8 = 1;
 
 #[account]
 pub struct StakeConfig {
@@ -34,6 +81,118 @@ impl UnbondingTicket {
     pub const LEN: usize = 8 + 32 + 8 + 8 + 8;
 }
 
+// Pending commit-reveal deposit of `user`. commitment = sha256(user || amount_le || salt).
+// Closed (rent back to the user) by reveal_deposit.
+#[account]
+pub struct DepositCommitment {
+    pub user: Pubkey,
+    pub commitment: [u8; 32],
+    pub committed_slot: u64, // slot of the latest commit_deposit
+    pub bump: u8,
+}
+
+impl DepositCommitment {
+    pub const LEN: usize = 8 + 32 + 32 + 8 + 1;
+}
+
+// Holder-balance snapshot committed by a rewards administrator, keyed by the slot at which an
+// off-chain indexer read the balances. leaf = sha256(holder || shares_le || slot_le); proofs walk
+// it up like vault-mint's reward claims (see ProofNode).
+#[account]
+pub struct SnapshotRoot {
+    pub merkle_root: [u8; 32],
+    pub total_shares_snapshot: u64, // sum of the snapshotted balances (stake mint supply at `slot`)
+    pub slot: u64,                  // slot the balances were read at
+    pub committed_by: Pubkey,
+    pub committed_at: i64, // unix timestamp of commit_snapshot
+    pub bump: u8,
+}
+
+impl SnapshotRoot {
+    pub const LEN: usize = 8 + 32 + 8 + 8 + 32 + 8 + 1;
+}
+
+/// One Merkle proof element. A zero sibling hashes the node alone.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct ProofNode {
+    pub sibling: [u8; 32],
+    pub is_left: bool,
+}
+
+// Stake-token airdrop published by a rewards administrator, keyed by its Merkle root. Holders
+// claim extra PRIME, minted by the mint authority, with a proof of
+// leaf = sha256(claimer || extra_shares_le). Nothing enters the vault, so the oracle price is
+// unchanged.
+#[account]
+pub struct StakeRewardDistribution {
+    pub merkle_root: [u8; 32],
+    pub total_shares: u64, // sum of the tree's extra_shares; claims never mint more
+    pub claimed_shares: u64, // minted by claim_stake_reward so far
+    pub published_by: Pubkey,
+    pub published_at: i64, // unix timestamp of publish_stake_reward
+    pub bump: u8,
+}
+
+impl StakeRewardDistribution {
+    pub const LEN: usize = 8 + 32 + 8 + 8 + 32 + 8 + 1;
+}
+
+#[account]
+pub struct ClaimRecord {} // empty marker account, existence = already claimed
+impl ClaimRecord {
+    pub const LEN: usize = 8;
+}
+
+// Audit record of a compliance restriction (freeze + reason) on a stake token account.
+// Kept after lift_restriction so the latest restrict/lift pair stays on-chain.
+#[account]
+pub struct RestrictionRecord {
+    pub token_account: Pubkey,
+    pub reason: String, // at most MAX_RESTRICTION_REASON_LEN bytes
+    pub restricted_by: Pubkey,
+    pub restricted_at: i64,
+    pub lifted_by: Pubkey, // Pubkey::default() while the restriction is active
+    pub lifted_at: i64,    // 0 while the restriction is active
+    pub bump: u8,
+}
+
+impl RestrictionRecord {
+    pub const LEN: usize = 8 + 32 + (4 + MAX_RESTRICTION_REASON_LEN) + 32 + 8 + 32 + 8 + 1;
+
+    pub fn is_active(&self) -> bool {
+        self.restricted_at > 0 && self.lifted_at == 0
+    }
+}
+
+// Per-user redeem bookkeeping for the redeem cooldown. Created by the user's first redeem.
+#[account]
+pub struct UserRedeemState {
+    pub user: Pubkey,
+    pub last_redeem_ts: i64, // unix timestamp of the user's latest successful redeem (0 = never)
+    pub bump: u8,
+}
+
+impl UserRedeemState {
+    pub const LEN: usize = 8 + 32 + 8 + 1;
+}
+
+// Linear vesting schedule for shares minted by deposit_vesting, held in the user's vesting escrow
+// (seeds [b"vesting_escrow", vesting_account]). A new vesting deposit replaces the schedule once
+// every share of the previous one has been claimed.
+#[account]
+pub struct VestingAccount {
+    pub user: Pubkey,
+    pub total_shares: u64, // shares minted into the escrow by the latest deposit_vesting
+    pub claimed_shares: u64, // shares already released by claim_vested
+    pub start_ts: i64,     // unix timestamp of the vesting deposit
+    pub duration_seconds: i64, // total_shares vest linearly from start_ts over this many seconds
+    pub bump: u8,
+}
+
+impl VestingAccount {
+    pub const LEN: usize = 8 + 32 + 8 + 8 + 8 + 8 + 1;
+}
+
 #[account]
 pub struct RewardPublicationRecord {
     pub id: u32,           // Unique identifier
@@ -126,3 +285,327 @@ impl StakePriceConfig {
     // 8 (discriminator) + 32 + 32 + 32 + 32 + 16 + 8 + 8 + 8 + 1 = 177
     pub const LEN: usize = 8 + 32 + 32 + 32 + 32 + 16 + 8 + 8 + 8 + 1;
 }
+
+// Operations config is a separate account (not part of StakeConfig) so that the deployed program's
+// account layout remains unchanged. This follows the same pattern as StakeRewardConfig.
+// deployed_assets is the amount of vault assets held outside the vault token account (e.g. placed
+// in a yield strategy). It is 0 until a strategy integration exists; total assets are always
+// vault_token_account.amount + deployed_assets.
+#[account]
+pub struct StakeOperationsConfig {
+    pub deployed_assets: u64, // vault assets reported as deployed outside the vault token account
+    pub total_donated: u64,   // lifetime total of vault tokens received through donate
+    // Share of total assets (BPS) that must stay idle in the vault token account after a redeem.
+    // 0 disables the reserve floor.
+    pub min_reserve_bps: u16,
+    // Program called (STAKE_HOOK_DISCRIMINATOR + StakeHookPayload) after every deposit and
+    // redeem. Pubkey::default() means no hook.
+    pub hook_program: Pubkey,
+    // When true, deposit/redeem fail if the hook program is not passed as the first remaining
+    // account; when false a caller may leave it out and skip the hook. It does not decide what
+    // happens when the hook fails: a failed hook CPI always reverts the transaction.
+    pub require_hook_account: bool,
+    // Enables commit_deposit / reveal_deposit. Plain deposit is unaffected either way.
+    pub commit_reveal_enabled: bool,
+    // Minimum number of slots between commit_deposit and reveal_deposit.
+    pub commit_reveal_delay_slots: u64,
+    // Break-glass migration override: when Some, deposit and redeem price at this rate
+    // (wYLDS per PRIME, scaled by EXCHANGE_RATE_SCALE) instead of the oracle price.
+    pub rate_override_1e9: Option<u64>,
+    // Circuit breaker: largest share of total assets (BPS) a single redeem may withdraw.
+    // 0 disables the breaker.
+    pub max_single_outflow_bps: u16,
+    // When true an over-threshold redeem pauses the protocol instead of failing.
+    pub circuit_breaker_auto_pause: bool,
+    pub largest_single_outflow_bps: u16, // largest share of total assets (BPS) withdrawn by one redeem
+    // Minimum seconds between a user's consecutive redeems (partial or full). 0 disables it.
+    pub redeem_cooldown_seconds: i64,
+    // Share of each deposit (BPS) routed to insurance_token_account instead of the vault; it is
+    // not counted as vault assets and mints no shares. 0 disables the split.
+    pub insurance_bps: u16,
+    // Vault-token account owned by the vault authority PDA that holds the insurance fund.
+    // Pubkey::default() until update_insurance_config sets it.
+    pub insurance_token_account: Pubkey,
+    // Unix time the protocol went from unpaused to paused (pause(true) or the circuit breaker);
+    // 0 while unpaused. Re-pausing an already paused protocol does not move it.
+    pub paused_at: i64,
+    // Seconds after paused_at from which redeem ignores the pause; at most MAX_PAUSE_DURATION.
+    pub max_pause_duration_seconds: i64,
+    // Value (vault-token base units * ROUNDING_DUST_SCALE) that deposit and redeem flooring has
+    // left in the vault: the unminted fraction of a share on deposit, the unpaid fraction of a
+    // vault token on redeem. Only ever grows.
+    pub rounding_dust_accumulated: u128,
+    // Incremented by every update_freeze_administrators / update_rewards_administrators; callers
+    // may pass the version they read as expected_version to reject stale (lost) updates.
+    pub admin_list_version: u64,
+    // The only program publish_rewards accepts as mint_program (and under which mint_config must
+    // be derived and owned). initialize_stake_operations_config sets it to vault-mint's id.
+    pub allowed_mint_program: Pubkey,
+    // Upper bound on the stake mint's raw supply after a deposit; None (the default) is unlimited.
+    pub max_total_shares: Option<u64>,
+    pub paused_operations: u8, // PAUSE_* bits; 0 pauses nothing
+    // Unix time PAUSE_REDEEM was set; 0 while it is clear. Like paused_at, it lets redeem through
+    // once older than max_pause_duration_seconds.
+    pub redeem_paused_at: i64,
+    // Rolling hash of every event this stake config emitted: sha256(events_digest || event bytes),
+    // where the bytes are those logged as "Program data" (discriminator + Borsh). Starts zeroed.
+    pub events_digest: [u8; 32],
+    // Last slot in which total assets or the stake supply changed (deposit, redeem, donate,
+    // reward publication, stake reward claims, insurance draws, deployed-asset reports). Events
+    // report it as totals_last_update_slot; 0 until the first change.
+    pub last_totals_update_slot: u64,
+    // publish_rewards requires the stake mint's raw supply to be at least this; 0 disables the
+    // check. Keeps a reward from accruing almost entirely to a first, tiny holder.
+    pub min_supply_for_rewards: u64,
+    // When set, update_freeze_administrators / update_rewards_administrators reject an empty
+    // list with CannotRemoveLastAdministrator. Both default to false.
+    pub require_nonempty_freeze_admins: bool,
+    pub require_nonempty_rewards_admins: bool,
+    // The only program zap_deposit may CPI into to swap an input token for the vault token;
+    // Pubkey::default() (the default) disables zaps.
+    pub swap_program: Pubkey,
+    // Seconds a RewardPublicationRecord must be kept; publish_rewards may close one older than
+    // this (passed as remaining_accounts[0]) and refund its rent. 0 (the default) keeps them all.
+    pub reward_record_retention: i64,
+    // publish_rewards mints through vault-mint only while this is set (the default); once the
+    // mint program is retired it fails with ExternalMintDisabled.
+    pub external_mint_enabled: bool,
+    // Vault-token account (owned by the vault authority) publish_rewards_from_reserve pays
+    // rewards from; Pubkey::default() until update_reward_reserve sets it.
+    pub reward_reserve_token_account: Pubkey,
+    pub bump: u8,
+}
+
+impl StakeOperationsConfig {
+    // discriminator + deployed_assets (u64) + total_donated (u64) + min_reserve_bps (u16) + hook_program (Pubkey) + require_hook_account (bool)
+    // + commit_reveal_enabled (bool) + commit_reveal_delay_slots (u64)
+    // + rate_override_1e9 (Option<u64>) + max_single_outflow_bps (u16)
+    // + circuit_breaker_auto_pause (bool) + largest_single_outflow_bps (u16)
+    // + redeem_cooldown_seconds (i64) + insurance_bps (u16) + insurance_token_account (Pubkey)
+    // + paused_at (i64) + max_pause_duration_seconds (i64) + rounding_dust_accumulated (u128)
+    // + admin_list_version (u64) + allowed_mint_program (Pubkey)
+    // + max_total_shares (Option<u64>) + paused_operations (u8) + redeem_paused_at (i64)
+    // + events_digest ([u8; 32]) + last_totals_update_slot (u64)
+    // + min_supply_for_rewards (u64) + require_nonempty_freeze_admins (bool)
+    // + require_nonempty_rewards_admins (bool) + swap_program (Pubkey)
+    // + reward_record_retention (i64) + external_mint_enabled (bool)
+    // + reward_reserve_token_account (Pubkey) + bump (u8)
+    pub const LEN: usize = 8
+        + 8
+        + 8
+        + 2
+        + 32
+        + 1
+        + 1
+        + 8
+        + (1 + 8)
+        + 2
+        + 1
+        + 2
+        + 8
+        + 2
+        + 32
+        + 8
+        + 8
+        + 16
+        + 8
+        + 32
+        + (1 + 8)
+        + 1
+        + 8
+        + 32
+        + 8
+        + 8
+        + 1
+        + 1
+        + 32
+        + 8
+        + 1
+        + 32
+        + 1;
+    pub const MAX_BPS: u16 = 10_000;
+}
+
+// Instruction data sent to the hook program: STAKE_HOOK_DISCRIMINATOR followed by the
+// Borsh-serialized StakeHookPayload. The discriminator is Anchor's for an instruction named
+// `on_stake_event` (sha256("global:on_stake_event")[..8]), so an Anchor hook program only needs
+// to declare `on_stake_event(ctx, payload: StakeHookPayload)`.
+pub const STAKE_HOOK_DISCRIMINATOR: [u8; 8] = [23, 182, 25, 74, 12, 45, 103, 238];
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StakeHookKind {
+    Deposit,
+    Redeem,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct StakeHookPayload {
+    pub kind: StakeHookKind,
+    pub user: Pubkey,
+    pub assets: u64,       // wYLDS deposited or withdrawn
+    pub shares: u64,       // PRIME minted or burned
+    pub total_assets: u64, // total assets after the operation
+    pub total_shares: u64, // total shares after the operation
+}
+
+// Reward policy config is a separate account (not part of StakeConfig) so that the deployed
+// program's account layout remains unchanged. This follows the same pattern as StakeRewardConfig.
+// budget_admins and budget_remaining are parallel arrays: budget_remaining[i] is the amount the
+// rewards administrator budget_admins[i] may still publish. Administrators without an entry are
+// not budget-limited; every entry must belong to a current rewards administrator, so both arrays
+// are bounded by MAX_ADMINISTRATORS.
+#[account]
+pub struct StakeRewardPolicyConfig {
+    pub budget_admins: Vec<Pubkey>, // rewards administrators with a publication budget
+    pub budget_remaining: Vec<u64>, // remaining budget of budget_admins[i] (raw token units)
+    pub disabled_admins: Vec<Pubkey>, // rewards administrators temporarily barred from publishing
+    pub reward_record_count: u64, // reward records created (publish_rewards + publish_epoch_marker)
+    // When true, publish_rewards and publish_epoch_marker reject ids below next_expected_reward_id.
+    pub enforce_monotonic_ids: bool,
+    // One past the highest reward id published so far; tracked even while enforcement is off.
+    // Kept as u64 so reaching u32::MAX is reported instead of wrapping back to 0.
+    pub next_expected_reward_id: u64,
+    pub bump: u8,
+}
+
+impl StakeRewardPolicyConfig {
+    // discriminator + budget_admins (4 + 32 * MAX_ADMINISTRATORS)
+    // + budget_remaining (4 + 8 * MAX_ADMINISTRATORS)
+    // + disabled_admins (4 + 32 * MAX_ADMINISTRATORS) + reward_record_count (u64)
+    // + enforce_monotonic_ids (bool) + next_expected_reward_id (u64) + bump (u8)
+    pub const LEN: usize = 8
+        + (4 + (32 * MAX_ADMINISTRATORS))
+        + (4 + (8 * MAX_ADMINISTRATORS))
+        + (4 + (32 * MAX_ADMINISTRATORS))
+        + 8
+        + 1
+        + 8
+        + 1;
+}
+
+// Return data of the shares_to_assets / assets_to_shares views. The decimals of both mints are
+// included so clients can format `amount` without fetching the mints. `amount` is serialized
+// first, so callers that only read the leading little-endian u64 are unaffected.
+// Every conversion view also returns the slot the quote was computed in, so a quote can be
+// recorded and compared with quotes from other transactions. The slot does not tell one call
+// apart from another in the same transaction, so it does not detect stale return data left by an
+// earlier instruction or CPI. The views are read-only and keep no per-call counter; a CPI caller
+// should read the return data right after its own call to the view.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ConversionResult {
+    pub amount: u64,        // converted amount in raw units of the target token
+    pub vault_decimals: u8, // decimals of the vault token mint (e.g. wYLDS)
+    pub share_decimals: u8, // decimals of the stake token mint (e.g. PRIME)
+    pub slot: u64,          // slot the view was computed in
+}
+
+// Return data of the convert_both view: assets_to_shares(assets) and shares_to_assets(shares)
+// from a single call.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ConvertBothResult {
+    pub shares_for_assets: u64, // PRIME for the `assets` argument
+    pub assets_for_shares: u64, // wYLDS for the `shares` argument
+    pub slot: u64,              // slot the view was computed in
+}
+
+// Return data of the exchange_rate view. `rate` is serialized first (see ConversionResult).
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ExchangeRateResult {
+    pub rate: u64, // wYLDS per PRIME, scaled by 1e9
+    pub slot: u64, // slot the view was computed in
+}
+
+// Return data of the exchange_rate_parts view: exchange_rate split into the whole number of
+// wYLDS per PRIME and the remainder, so integer-only clients avoid floating point.
+// whole * 1e9 + frac_1e9 == exchange_rate.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ExchangeRateParts {
+    pub whole: u64,    // floor(wYLDS per PRIME)
+    pub frac_1e9: u64, // fractional wYLDS per PRIME, scaled by 1e9
+    pub slot: u64,     // slot the view was computed in
+}
+
+// Return data of the price_feed view: exchange_rate in the Pyth price/expo shape, so oracle
+// relayers can publish it as-is. price * 10^expo == wYLDS per PRIME.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct PriceFeed {
+    pub price: i64, // exchange_rate (wYLDS per PRIME scaled by 1e9)
+    pub expo: i32,  // always -9, matching EXCHANGE_RATE_SCALE
+    pub conf: u64,  // PRICE_FEED_CONFIDENCE, in the same units as price
+    pub slot: u64,  // slot the view was computed in
+}
+
+// One remaining account checked by verify_vault_ownership, in the order passed.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct VaultOwnershipEntry {
+    pub mint: Pubkey,                   // mint of the token account
+    pub owned_by_vault_authority: bool, // token account owner is the vault authority PDA
+    pub canonical: bool,                // is StakeVaultTokenAccountConfig.vault_token_account
+    pub insurance: bool,                // is StakeOperationsConfig.insurance_token_account
+}
+
+// Return data of the verify_vault_ownership view. unexpected_count counts the entries owned by the
+// vault authority that are neither the canonical vault token account nor the insurance account.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct VaultOwnershipReport {
+    pub entries: Vec<VaultOwnershipEntry>,
+    pub unexpected_count: u8,
+}
+
+// Return data of the display_rate view: the canonical "1 PRIME = X wYLDS" figures for UIs, in
+// whole tokens (already adjusted for both mints' decimals) and scaled by 1e9.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct DisplayRate {
+    pub assets_per_share_1e9: u64, // wYLDS per 1 PRIME, scaled by 1e9
+    pub shares_per_asset_1e9: u64, // PRIME per 1 wYLDS, scaled by 1e9
+    pub vault_decimals: u8,        // decimals of the vault token mint (e.g. wYLDS)
+    pub share_decimals: u8,        // decimals of the stake token mint (e.g. PRIME)
+    pub slot: u64,                 // slot the view was computed in
+}
+
+// Return data of the get_ticket view: the stored fields of a legacy unbonding ticket. Unbonding
+// periods were removed, so a legacy ticket never gates a redeem: its owner can redeem at any time,
+// and the next redeem (or cancel_unbond) closes it. There is therefore no redeemable flag or
+// remaining wait to report.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct TicketStatus {
+    pub requested_amount: u64,
+    pub start_ts: i64,
+    pub start_balance: u64,
+}
+
+// Who holds the stake mint's freeze authority, as reported by validate_freeze_setup.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FreezeAuthorityStatus {
+    ProgramControlled, // the freeze_authority PDA: freeze/thaw instructions can succeed
+    External,          // another key: run claim_freeze_authority with that key first
+    None,              // the mint has no freeze authority: accounts can never be frozen
+}
+
+// Return data of the validate_freeze_setup view.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct FreezeSetup {
+    pub status: FreezeAuthorityStatus,
+    pub freeze_authority: Option<Pubkey>,
+    pub freeze_administrator_count: u8,
+    pub ready: bool, // program controls freeze and at least one freeze administrator is set
+}
+
+// Return data of the get_mints view: the token identities configured in StakeConfig.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct StakeMints {
+    pub mint: Pubkey,  // stake token mint (e.g. PRIME)
+    pub vault: Pubkey, // vault token mint (e.g. wYLDS)
+}
+
+// Return data of the timing_params view: every timing setting a front-end needs to show when
+// deposits, redeems and reward publications become possible, independent of the config layouts.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct TimingParams {
+    pub unbonding_period: i64,           // always 0: redeem settles immediately
+    pub redeem_cooldown_seconds: i64,    // minimum seconds between a user's redeems (0 = none)
+    pub price_max_staleness: i64,        // seconds the stored price stays usable
+    pub max_pause_duration_seconds: i64, // seconds a pause may block redeem
+    pub reward_period_seconds: i64,      // cooldown between reward publications
+    pub commit_reveal_delay_slots: u64,  // slots between commit_deposit and reveal_deposit
+}
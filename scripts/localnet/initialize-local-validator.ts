@@ -340,7 +340,8 @@ async function main() {
         const tx = await stakeProgram.methods
             .initialize(
                 [freezeAdmin.publicKey],
-                [rewardsAdmin.publicKey]
+                [rewardsAdmin.publicKey],
+                null
             )
             .accountsStrict({
                 stakeConfig: stakeConfigPda,
@@ -463,7 +464,8 @@ async function main() {
         const tx = await stakeAutoProgram.methods
             .initialize(
                 [freezeAdmin.publicKey],
-                [rewardsAdmin.publicKey]
+                [rewardsAdmin.publicKey],
+                null
             )
             .accountsStrict({
                 stakeConfig: stakeAutoConfigPda,
@@ -180,8 +180,16 @@ async function main() {
         [Buffer.from("stake_price_config"), stakeConfigPda.toBuffer()],
         thisProgramId
     );
+    const [stakeOperationsConfigPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("stake_operations_config"), stakeConfigPda.toBuffer()],
+        thisProgramId
+    );
 
     const signer = provider.wallet.publicKey;
+    const [redeemStatePda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("redeem_state"), stakeConfigPda.toBuffer(), signer.toBuffer()],
+        thisProgramId
+    );
 
     let mint: anchor.web3.PublicKey;
     let vaultMint: anchor.web3.PublicKey;
@@ -322,6 +330,7 @@ async function main() {
             stakeConfig: stakeConfigPda,
             stakeVaultTokenAccountConfig: stakeVaultTokenAccountConfigPda,
             stakePriceConfig: stakePriceConfigPda,
+            stakeOperationsConfig: stakeOperationsConfigPda,
             vaultTokenAccount: vaultTokenAccount,
             vaultAuthority: vaultAuthorityPda,
             signer: signer,
@@ -330,7 +339,9 @@ async function main() {
             userMintTokenAccount: userMintTokenAccount,
             mint: mint,
             vaultMint: vaultMint,
+            redeemState: redeemStatePda,
             tokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
+            systemProgram: anchor.web3.SystemProgram.programId,
         })
         .rpc();
 
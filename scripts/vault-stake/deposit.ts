@@ -47,6 +47,11 @@ const main = async () => {
         program.programId
     );
 
+    const [stakeOperationsConfigPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("stake_operations_config"), stakeConfigPda.toBuffer()],
+        program.programId
+    );
+
     const [vaultAuthorityPda] = anchor.web3.PublicKey.findProgramAddressSync(
         [Buffer.from("vault_authority")],
         program.programId
@@ -84,12 +89,20 @@ const main = async () => {
     console.log("Mint Authority PDA:", mintAuthorityPda.toBase58());
     console.log("Vault Authority PDA:", vaultAuthorityPda.toBase58());
 
+    // The insurance fund only has to be passed when a share of each deposit is routed to it.
+    const operationsConfig = await program.account.stakeOperationsConfig.fetch(stakeOperationsConfigPda);
+    const insuranceTokenAccount = operationsConfig.insuranceBps > 0
+        ? operationsConfig.insuranceTokenAccount
+        : program.programId;
+
     const tx = await program.methods
         .deposit(amount)
         .accountsStrict({
             stakeConfig: stakeConfigPda,
             stakeVaultTokenAccountConfig: stakeVaultTokenAccountConfigPda,
             stakePriceConfig: stakePriceConfigPda,
+            stakeOperationsConfig: stakeOperationsConfigPda,
+            insuranceTokenAccount: insuranceTokenAccount,
             vaultTokenAccount: vaultTokenAccount,
             vaultAuthority: vaultAuthorityPda,
             mint: mint,
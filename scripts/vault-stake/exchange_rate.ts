@@ -35,6 +35,11 @@ const main = async () => {
         program.programId
     );
 
+    const [stakeOperationsConfigPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("stake_operations_config"), stakeConfigPda.toBuffer()],
+        program.programId
+    );
+
     const [vaultAuthorityPda] = anchor.web3.PublicKey.findProgramAddressSync(
         [Buffer.from("vault_authority")],
         program.programId
@@ -49,6 +54,8 @@ const main = async () => {
     console.log("Vault Token Account:", vaultTokenAccount.toBase58());
     console.log("Vault Authority PDA:", vaultAuthorityPda.toBase58());
 
+    const stakeConfig = await program.account.stakeConfig.fetch(stakeConfigPda);
+
     // Call exchange_rate
     const sig = await program.methods
         .exchangeRate()
@@ -57,7 +64,9 @@ const main = async () => {
             stakePriceConfig: stakePriceConfigPda,
             vaultAuthority: vaultAuthorityPda,
             mint: mint,
+            vaultMint: stakeConfig.vault,
             vaultTokenAccount: vaultTokenAccount,
+            stakeOperationsConfig: stakeOperationsConfigPda,
         })
         .rpc()
 
@@ -71,13 +80,15 @@ const main = async () => {
     if (!!s?.meta["returnData"]) {
         const returnData = s!.meta!["returnData"].data;
         const buffer = Buffer.from(returnData[0], returnData[1]);
-        // exchange_rate() returns a u64 scaled by 1e9 (see processor.rs SCALE constant).
+        // exchange_rate() returns ExchangeRateResult { rate, slot, nonce }; rate is a u64 scaled
+        // by 1e9 (see EXCHANGE_RATE_SCALE in state.rs).
         // e.g. 1_000_000_000 → 1.000000000 wYLDS per PRIME
         const SCALE = BigInt(1_000_000_000);
-        const raw = BigInt(new anchor.BN(buffer, "le").toString());
+        const raw = buffer.readBigUInt64LE(0);
         const whole = raw / SCALE;
         const frac = (raw % SCALE).toString().padStart(9, "0");
         console.log(`Exchange Rate: ${whole}.${frac} wYLDS per PRIME  (raw: ${raw})`);
+        console.log(`Computed in slot ${buffer.readBigUInt64LE(8)} (view nonce ${buffer.readBigUInt64LE(16)})`);
     }
 };
 
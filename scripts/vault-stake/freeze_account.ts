@@ -32,6 +32,11 @@ const main = async () => {
         program.programId
     );
 
+    const [stakeOperationsConfigPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("stake_operations_config"), stakeConfigPda.toBuffer()],
+        program.programId
+    );
+
     const [freezeAuthorityPda] = PublicKey.findProgramAddressSync(
         [Buffer.from("freeze_authority")],
         program.programId
@@ -58,6 +63,7 @@ const main = async () => {
             .freezeTokenAccount()
             .accountsStrict({
                 stakeConfig: stakeConfigPda,
+                stakeOperationsConfig: stakeOperationsConfigPda,
                 tokenAccount: tokenAccount,
                 mint: mint,
                 freezeAuthorityPda: freezeAuthorityPda,
@@ -35,6 +35,11 @@ const args = yargs(process.argv.slice(2))
         description: "Comma separated list of administrator public keys that can execute user staking distribution rewards.",
         required: true,
     })
+    .option("allow_prefunded_vault", {
+        type: "boolean",
+        description: "Allow initializing with a vault token account that already holds tokens (skips the empty-vault check).",
+        default: false,
+    })
     .option("program_id", {
         type: "string",
         description: "Optional program id override (use vault-stake script against stake-auto deployment).",
@@ -113,7 +118,7 @@ const main = async () => {
 
     // Call initialize
     await program.methods
-        .initialize(freezeAdministrators, rewardsAdministrators)
+        .initialize(freezeAdministrators, rewardsAdministrators, !args.allow_prefunded_vault)
         .accountsStrict({
             stakeConfig: stakeConfigPda,
             vaultAuthority: vaultAuthorityPda,
@@ -22,6 +22,11 @@ const main = async () => {
         program.programId
     );
 
+    const [stakeOperationsConfigPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("stake_operations_config"), stakeConfigPda.toBuffer()],
+        program.programId
+    );
+
     console.log("Program ID:", program.programId.toBase58());
     console.log("Stake Config PDA:", stakeConfigPda.toBase58());
 
@@ -30,6 +35,7 @@ const main = async () => {
         .pause(args.pause)
         .accountsStrict({
             stakeConfig: stakeConfigPda,
+            stakeOperationsConfig: stakeOperationsConfigPda,
             signer: provider.wallet.publicKey,
         })
         .rpc()
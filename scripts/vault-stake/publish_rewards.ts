@@ -73,6 +73,22 @@ const main = async () => {
         program.programId
     );
 
+    const [stakeOperationsConfigPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [
+            Buffer.from("stake_operations_config"),
+            stakeConfigPda.toBuffer()
+        ],
+        program.programId
+    );
+
+    const [stakeRewardPolicyConfigPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [
+            Buffer.from("stake_reward_policy_config"),
+            stakeConfigPda.toBuffer()
+        ],
+        program.programId
+    );
+
     const [vaultAuthorityPda] = anchor.web3.PublicKey.findProgramAddressSync(
         [Buffer.from("vault_authority")],
         program.programId
@@ -150,6 +166,8 @@ const main = async () => {
             mint: mint,
             rewardRecord: rewardsRecordPda,
             stakeRewardConfig: stakeRewardConfigPda,
+            stakeOperationsConfig: stakeOperationsConfigPda,
+            stakeRewardPolicyConfig: stakeRewardPolicyConfigPda,
             tokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
             systemProgram: anchor.web3.SystemProgram.programId,
         }).rpc();
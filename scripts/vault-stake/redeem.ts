@@ -69,6 +69,18 @@ const main = async () => {
         program.programId
     );
 
+    const [stakeOperationsConfigPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("stake_operations_config"), stakeConfigPda.toBuffer()],
+        program.programId
+    );
+
+    // Per-user redeem cooldown state; created by the program on the first redeem. Only required
+    // while a redeem cooldown is configured, but always valid to pass.
+    const [redeemStatePda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("redeem_state"), stakeConfigPda.toBuffer(), signer.toBuffer()],
+        program.programId
+    );
+
 
     // The unbonding flow was removed. The ticket account is now optional:
     //   - If a legacy UnbondingTicket PDA exists on-chain, pass its address so the
@@ -103,6 +115,7 @@ const main = async () => {
             stakeConfig: stakeConfigPda,
             stakeVaultTokenAccountConfig: stakeVaultTokenAccountConfigPda,
             stakePriceConfig: stakePriceConfigPda,
+            stakeOperationsConfig: stakeOperationsConfigPda,
             vaultTokenAccount: vaultTokenAccount,
             vaultAuthority: vaultAuthorityPda,
             signer: signer,
@@ -111,7 +124,9 @@ const main = async () => {
             userMintTokenAccount: userMintTokenAccount,
             mint: mint,
             vaultMint: vaultMint,
+            redeemState: redeemStatePda,
             tokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
+            systemProgram: anchor.web3.SystemProgram.programId,
         }).rpc();
 
     console.log("Transaction:", tx);
@@ -0,0 +1,72 @@
+import * as anchor from "@coral-xyz/anchor";
+import {Program} from "@coral-xyz/anchor";
+import {VaultStake} from "../../target/types/vault_stake";
+import {PublicKey} from "@solana/web3.js";
+import yargs from "yargs";
+
+const provider = anchor.AnchorProvider.env();
+anchor.setProvider(provider);
+
+const program = anchor.workspace.VaultStake as Program<VaultStake>;
+
+const args = yargs(process.argv.slice(2))
+    .option("admin", {
+        type: "string",
+        description: "Rewards administrator public key whose publication budget is set",
+        required: true,
+    })
+    .option("budget", {
+        type: "string",
+        description: "Remaining amount (raw wYLDS units) the administrator may publish",
+        required: true,
+    })
+    .parseSync();
+
+const main = async () => {
+    const admin = new PublicKey(args.admin);
+    const budget = new anchor.BN(args.budget);
+
+    const [stakeConfigPda] = PublicKey.findProgramAddressSync([
+        Buffer.from("stake_config")
+    ], program.programId);
+
+    const [stakeOperationsConfigPda] = PublicKey.findProgramAddressSync([
+        Buffer.from("stake_operations_config"), stakeConfigPda.toBuffer()
+    ], program.programId);
+
+    const [stakeRewardPolicyConfigPda] = PublicKey.findProgramAddressSync([
+        Buffer.from("stake_reward_policy_config"),
+        stakeConfigPda.toBuffer()
+    ], program.programId);
+
+    // bpf_loader_upgradeable program id
+    const BPF_LOADER_UPGRADEABLE_ID = new PublicKey(
+        "BPFLoaderUpgradeab1e11111111111111111111111"
+    );
+    // derive ProgramData PDA
+    const [programData] = PublicKey.findProgramAddressSync(
+        [program.programId.toBuffer()],
+        BPF_LOADER_UPGRADEABLE_ID
+    );
+
+    console.log("Stake Config PDA:", stakeConfigPda.toBase58());
+    console.log("Stake Reward Policy Config PDA:", stakeRewardPolicyConfigPda.toBase58());
+    console.log("ProgramData PDA:", programData.toBase58());
+    console.log("Rewards Administrator:", admin.toBase58());
+    console.log("Budget:", budget.toString());
+
+    const tx = await program.methods
+        .setRewardBudget(admin, budget)
+        .accountsStrict({
+            stakeConfig: stakeConfigPda,
+            stakeOperationsConfig: stakeOperationsConfigPda,
+            stakeRewardPolicyConfig: stakeRewardPolicyConfigPda,
+            signer: provider.wallet.publicKey,
+            programData: programData,
+        })
+        .rpc();
+
+    console.log("Transaction:", tx);
+};
+
+main().catch(console.error);
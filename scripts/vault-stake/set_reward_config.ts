@@ -65,6 +65,10 @@ async function main() {
         [Buffer.from("stake_config")],
         program.programId
     );
+    const [stakeOperationsConfigPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("stake_operations_config"), stakeConfigPda.toBuffer()],
+        program.programId
+    );
     const [stakeRewardConfigPda] = PublicKey.findProgramAddressSync(
         [Buffer.from("stake_reward_config"), stakeConfigPda.toBuffer()],
         program.programId
@@ -77,6 +81,7 @@ async function main() {
     const signer = provider.wallet.publicKey;
     const adminAccounts = {
         stakeConfig: stakeConfigPda,
+        stakeOperationsConfig: stakeOperationsConfigPda,
         stakeRewardConfig: stakeRewardConfigPda,
         signer,
         programData: programDataPda,
@@ -126,6 +126,10 @@ async function main() {
         [Buffer.from("stake_config")],
         program.programId
     );
+    const [stakeOperationsConfigPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("stake_operations_config"), stakeConfigPda.toBuffer()],
+        program.programId
+    );
     const [stakeRewardConfigPda] = PublicKey.findProgramAddressSync(
         [Buffer.from("stake_reward_config"), stakeConfigPda.toBuffer()],
         program.programId
@@ -160,6 +164,7 @@ async function main() {
 
     const adminAccounts = {
         stakeConfig: stakeConfigPda,
+        stakeOperationsConfig: stakeOperationsConfigPda,
         stakeRewardConfig: stakeRewardConfigPda,
         signer: vaultPda,
         programData: programDataPda,
@@ -73,6 +73,11 @@ async function main() {
         program.programId
     );
 
+    const [stakeOperationsConfigPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("stake_operations_config"), stakeConfigPda.toBuffer()],
+        program.programId
+    );
+
     const [stakePriceConfigPda] = PublicKey.findProgramAddressSync(
         [Buffer.from("stake_price_config"), stakeConfigPda.toBuffer()],
         program.programId
@@ -146,6 +151,7 @@ async function main() {
         )
         .accountsStrict({
             stakeConfig: stakeConfigPda,
+            stakeOperationsConfig: stakeOperationsConfigPda,
             stakePriceConfig: stakePriceConfigPda,
             signer: vaultPda,
             programData: programData,
@@ -15,6 +15,11 @@ const args = yargs(process.argv.slice(2))
         description: "Comma separated list of administrator public keys that can initiate user staking rewards",
         required: true,
     })
+    .option("expected_version", {
+        type: "number",
+        description: "admin_list_version read before building this update; the update fails if the lists changed since",
+        required: false,
+    })
     .parseSync();
 
 const main = async () => {
@@ -26,6 +31,10 @@ const main = async () => {
     const [stakeConfigPda, bump] = PublicKey.findProgramAddressSync([
         Buffer.from("stake_config")
     ], program.programId);
+    const [stakeOperationsConfigPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("stake_operations_config"), stakeConfigPda.toBuffer()],
+        program.programId
+    );
 
     // bpf_loader_upgradeable program id
     const BPF_LOADER_UPGRADEABLE_ID = new PublicKey(
@@ -42,9 +51,13 @@ const main = async () => {
     console.log("Rewards Administrators:", rewardsAdministrators.map((a) => a.toBase58()));
 
     const tx = await program.methods
-        .updateRewardsAdministrators(rewardsAdministrators)
+        .updateRewardsAdministrators(
+            rewardsAdministrators,
+            args.expected_version === undefined ? null : new anchor.BN(args.expected_version)
+        )
         .accountsStrict({
             stakeConfig: stakeConfigPda,
+            stakeOperationsConfig: stakeOperationsConfigPda,
             signer: provider.wallet.publicKey,
             programData: programData,
         })
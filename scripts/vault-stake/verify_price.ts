@@ -25,6 +25,11 @@ async function main() {
         program.programId
     );
 
+    const [stakeOperationsConfigPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("stake_operations_config"), stakeConfigPda.toBuffer()],
+        program.programId
+    );
+
     const [stakePriceConfigPda] = PublicKey.findProgramAddressSync(
         [Buffer.from("stake_price_config"), stakeConfigPda.toBuffer()],
         program.programId
@@ -78,6 +83,7 @@ async function main() {
         .verifyPrice(compressedReport)
         .accountsStrict({
             stakeConfig: stakeConfigPda,
+            stakeOperationsConfig: stakeOperationsConfigPda,
             stakePriceConfig: stakePriceConfigPda,
             chainlinkVerifierAccount: priceConfig.chainlinkVerifierAccount,
             chainlinkAccessController: priceConfig.chainlinkAccessController,
@@ -64,6 +64,35 @@ export const STAKE_REWARD_CONFIG_DEFAULTS = {
     maxTotalRewards: new BN("10000000000000"),
 };
 
+/**
+ * Compute-unit ceilings for vault-stake's mutating instructions (see README "Compute budget").
+ * Benchmark tests fail if an instruction consumes more than its ceiling, so a regression has to
+ * come with a deliberate (documented) ceiling change.
+ */
+export const STAKE_COMPUTE_UNIT_CEILINGS = {
+    deposit: 80_000,
+    redeem: 80_000,
+    publishRewards: 150_000,
+};
+
+/** Compute units consumed by a confirmed transaction, as metered by the validator. */
+export async function getComputeUnitsConsumed(
+    connection: Connection,
+    sig: string
+): Promise<number> {
+    const latestBlockhash = await connection.getLatestBlockhash("confirmed");
+    await connection.confirmTransaction({ signature: sig, ...latestBlockhash }, "confirmed");
+    const tx = await connection.getTransaction(sig, {
+        commitment: "confirmed",
+        maxSupportedTransactionVersion: 0,
+    });
+    const consumed = tx?.meta?.computeUnitsConsumed;
+    if (consumed === undefined) {
+        throw new Error(`No compute units reported for ${sig}`);
+    }
+    return consumed;
+}
+
 export async function getTokenBalance(
     connection: Connection,
     tokenAccount: PublicKey
@@ -1362,6 +1362,8 @@ describe("vault-mint", () => {
         let stakeVaultTokenAccountConfigPdaAuto: PublicKey;
         let stakePriceConfigPdaAuto: PublicKey;
         let stakeRewardConfigPdaAuto: PublicKey;
+        let stakeOperationsConfigPdaAuto: PublicKey;
+        let stakeRewardPolicyConfigPdaAuto: PublicKey;
         let programDataPdaAuto: PublicKey;
         let externalMintAuthorityPdaAuto: PublicKey;
         let autoShareMint: PublicKey;
@@ -1442,6 +1444,8 @@ describe("vault-mint", () => {
             mint: autoShareMint,
             rewardRecord,
             stakeRewardConfig: stakeRewardConfigPdaAuto,
+            stakeOperationsConfig: stakeOperationsConfigPdaAuto,
+            stakeRewardPolicyConfig: stakeRewardPolicyConfigPdaAuto,
             tokenProgram: TOKEN_PROGRAM_ID,
             systemProgram: SystemProgram.programId,
         });
@@ -1499,6 +1503,20 @@ describe("vault-mint", () => {
                 ],
                 stakeAutoProgram.programId
             );
+            [stakeOperationsConfigPdaAuto] = PublicKey.findProgramAddressSync(
+                [
+                    Buffer.from("stake_operations_config"),
+                    stakeConfigPdaAuto.toBuffer(),
+                ],
+                stakeAutoProgram.programId
+            );
+            [stakeRewardPolicyConfigPdaAuto] = PublicKey.findProgramAddressSync(
+                [
+                    Buffer.from("stake_reward_policy_config"),
+                    stakeConfigPdaAuto.toBuffer(),
+                ],
+                stakeAutoProgram.programId
+            );
             [programDataPdaAuto] = PublicKey.findProgramAddressSync(
                 [stakeAutoProgram.programId.toBuffer()],
                 BPF_LOADER_UPGRADEABLE_ID
@@ -1512,16 +1530,12 @@ describe("vault-mint", () => {
                 [Buffer.from("mint_authority")],
                 stakeAutoProgram.programId
             );
-            const [autoFreezeAuthorityPda] = PublicKey.findProgramAddressSync(
-                [Buffer.from("freeze_authority")],
-                stakeAutoProgram.programId
-            );
-
+            // Freeze authority starts with the wallet; claim_freeze_authority hands it to the PDA.
             autoShareMint = await createMint(
                 provider.connection,
                 provider.wallet.payer,
                 autoMintAuthorityPda,
-                autoFreezeAuthorityPda,
+                provider.wallet.publicKey,
                 6
             );
 
@@ -1580,8 +1594,9 @@ describe("vault-mint", () => {
                 fundVault
             );
 
+            // The auto vault is pre-funded on purpose, so opt out of the empty-vault check.
             await stakeAutoProgram.methods
-                .initialize([freezeAdmin.publicKey], [rewardsAdmin.publicKey])
+                .initialize([freezeAdmin.publicKey], [rewardsAdmin.publicKey], false)
                 .accountsStrict({
                     stakeConfig: stakeConfigPdaAuto,
                     vaultAuthority: vaultAuthorityPdaAuto,
@@ -1614,11 +1629,108 @@ describe("vault-mint", () => {
                 })
                 .rpc();
 
+            await stakeAutoProgram.methods
+                .initializeStakeOperationsConfig()
+                .accountsStrict({
+                    stakeConfig: stakeConfigPdaAuto,
+                    stakeOperationsConfig: stakeOperationsConfigPdaAuto,
+                    signer: provider.wallet.publicKey,
+                    programData: programDataPdaAuto,
+                    systemProgram: SystemProgram.programId,
+                })
+                .rpc();
+
+            await stakeAutoProgram.methods
+                .initializeStakeRewardPolicyConfig()
+                .accountsStrict({
+                    stakeConfig: stakeConfigPdaAuto,
+                    stakeRewardPolicyConfig: stakeRewardPolicyConfigPdaAuto,
+                    signer: provider.wallet.publicKey,
+                    programData: programDataPdaAuto,
+                    systemProgram: SystemProgram.programId,
+                })
+                .rpc();
+
             // stake_reward_config for AUTO is created lazily on first publish_rewards.
 
             await setPriceForTestingAuto();
         });
 
+        it("vault-stake validate_freeze_setup reports an external freeze authority before it is claimed", async function () {
+            if (!autoProgramDeployed) {
+                this.skip();
+                return;
+            }
+            const [autoFreezeAuthorityPda] = PublicKey.findProgramAddressSync(
+                [Buffer.from("freeze_authority")],
+                stakeAutoProgram.programId
+            );
+            const sig = await stakeAutoProgram.methods
+                .validateFreezeSetup()
+                .accountsStrict({
+                    stakeConfig: stakeConfigPdaAuto,
+                    mint: autoShareMint,
+                    freezeAuthorityPda: autoFreezeAuthorityPda,
+                })
+                .rpc({ commitment: "confirmed" });
+            const tx = await provider.connection.getTransaction(sig, {
+                commitment: "confirmed",
+                maxSupportedTransactionVersion: 0,
+            });
+            const [data, encoding] = tx!.meta!.returnData!.data;
+            const buffer = Buffer.from(data, encoding as BufferEncoding);
+            // status (u8: 0 ProgramControlled, 1 External, 2 None) | Option<Pubkey> | admin count (u8) | ready (bool)
+            assert.equal(buffer[0], 1, "External: the wallet still holds the freeze authority");
+            assert.equal(buffer[1], 1);
+            assert.ok(new PublicKey(buffer.subarray(2, 34)).equals(provider.wallet.publicKey));
+            assert.equal(buffer[34], 1, "one freeze administrator configured");
+            assert.equal(buffer[35], 0, "freeze/thaw would fail until claim_freeze_authority runs");
+        });
+
+        it("vault-stake claim_freeze_authority moves the share mint freeze authority to the program PDA", async function () {
+            if (!autoProgramDeployed) {
+                this.skip();
+                return;
+            }
+            const [autoFreezeAuthorityPda] = PublicKey.findProgramAddressSync(
+                [Buffer.from("freeze_authority")],
+                stakeAutoProgram.programId
+            );
+            const freezeAuthorityViewAccounts = {
+                stakeConfig: stakeConfigPdaAuto,
+                mint: autoShareMint,
+                freezeAuthorityPda: autoFreezeAuthorityPda,
+            };
+            const isProgramFreezeAuthority = async (): Promise<boolean> => {
+                const sig = await stakeAutoProgram.methods
+                    .validateFreezeAuthority()
+                    .accountsStrict(freezeAuthorityViewAccounts)
+                    .rpc({ commitment: "confirmed" });
+                const tx = await provider.connection.getTransaction(sig, {
+                    commitment: "confirmed",
+                    maxSupportedTransactionVersion: 0,
+                });
+                const [data, encoding] = tx!.meta!.returnData!.data;
+                return Buffer.from(data, encoding as BufferEncoding)[0] === 1;
+            };
+
+            assert.isFalse(await isProgramFreezeAuthority(), "wallet still holds the freeze authority");
+
+            await stakeAutoProgram.methods
+                .claimFreezeAuthority()
+                .accountsStrict({
+                    ...freezeAuthorityViewAccounts,
+                    signer: provider.wallet.publicKey,
+                    programData: programDataPdaAuto,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                })
+                .rpc();
+
+            const mintInfo = await getMint(provider.connection, autoShareMint);
+            assert.ok(mintInfo.freezeAuthority.equals(autoFreezeAuthorityPda));
+            assert.isTrue(await isProgramFreezeAuthority());
+        });
+
         it("legacy path: config.allowedExternalMintProgram is vault-stake (CPI exercised in vault-stake.test.ts publish_rewards)", async () => {
             const cfg = await program.account.config.fetch(configPda);
             assert.ok(
@@ -2068,6 +2180,160 @@ describe("vault-mint", () => {
                 expect(err.toString()).to.match(/EpochCapExceeded|epoch cap/i);
             }
         });
+
+        describe("cohort rewards", () => {
+            const cohortIndex = 60;
+            const earlyBird = Keypair.generate().publicKey;
+            let cohortMerkle: ReturnType<typeof allocationsToMerkleTree>;
+            let cohortEpochPda: PublicKey;
+            let cohortClaimPda: PublicKey;
+            let cutoffTs: number;
+
+            const cohortPdaFor = (index: number) => PublicKey.findProgramAddressSync(
+                [Buffer.from("cohort"), new anchor.BN(index).toArrayLike(Buffer, "le", 8)],
+                program.programId
+            )[0];
+            const proofFor = (merkle: ReturnType<typeof allocationsToMerkleTree>, owner: PublicKey, amount: anchor.BN, index: number) =>
+                merkle.tree.getProof(makeLeaf(owner, amount, index)).map(p => ({
+                    sibling: Array.from(p.data),
+                    isLeft: p.position === "left",
+                }));
+
+            before(async () => {
+                // Snapshot: holders before the cutoff (user and another early bird) are allocated.
+                const allocations = {
+                    allocations: [
+                        { account: user.publicKey.toBase58(), amount: 700 },
+                        { account: earlyBird.toBase58(), amount: 300 },
+                    ],
+                };
+                cohortMerkle = allocationsToMerkleTree(JSON.stringify(allocations), cohortIndex);
+                ({ epoch: cohortEpochPda } = deriveRewardsEpochV2Accounts(program.programId, cohortIndex));
+                [cohortClaimPda] = PublicKey.findProgramAddressSync(
+                    [Buffer.from("claim"), cohortEpochPda.toBuffer(), user.publicKey.toBuffer()],
+                    program.programId
+                );
+                cutoffTs = (await provider.connection.getBlockTime(await provider.connection.getSlot())) - 60;
+            });
+
+            it("rejects a cohort cutoff in the future", async () => {
+                const futureIndex = 61;
+                const { epoch: futureEpochPda } = deriveRewardsEpochV2Accounts(program.programId, futureIndex);
+                try {
+                    await program.methods
+                        .publishCohortRewards(
+                            new anchor.BN(futureIndex),
+                            Array.from(cohortMerkle.tree.getRoot()),
+                            new BN(1000),
+                            new anchor.BN(cutoffTs + 3600)
+                        )
+                        .accountsStrict({
+                            rewardsEpoch: createV2Accounts(futureEpochPda, futureIndex),
+                            cohort: cohortPdaFor(futureIndex),
+                            systemProgram: anchor.web3.SystemProgram.programId,
+                        })
+                        .signers([rewardsAdmin])
+                        .rpc();
+                    assert.fail("Should have thrown InvalidCohortCutoff");
+                } catch (err) {
+                    expect(err.toString()).to.include("InvalidCohortCutoff");
+                }
+            });
+
+            it("publishes cohort rewards as a funded V2 epoch with the snapshot cutoff", async () => {
+                const total = new BN(1000);
+                await program.methods
+                    .publishCohortRewards(
+                        new anchor.BN(cohortIndex),
+                        Array.from(cohortMerkle.tree.getRoot()),
+                        total,
+                        new anchor.BN(cutoffTs)
+                    )
+                    .accountsStrict({
+                        rewardsEpoch: createV2Accounts(cohortEpochPda, cohortIndex),
+                        cohort: cohortPdaFor(cohortIndex),
+                        systemProgram: anchor.web3.SystemProgram.programId,
+                    })
+                    .signers([rewardsAdmin])
+                    .rpc();
+
+                const cohort = await program.account.cohortRewards.fetch(cohortPdaFor(cohortIndex));
+                assert.equal(cohort.index.toNumber(), cohortIndex);
+                assert.equal(cohort.cutoffTs.toNumber(), cutoffTs);
+                const { epochRewardsPool } = deriveRewardsEpochV2Accounts(program.programId, cohortIndex);
+                assert.equal(await getTokenBalance(provider.connection, epochRewardsPool), BigInt(1000));
+            });
+
+            it("rejects an invalid cohort proof (wrong amount)", async () => {
+                const proof = proofFor(cohortMerkle, user.publicKey, new anchor.BN(700), cohortIndex);
+                try {
+                    await program.methods
+                        .claimCohortRewards(new anchor.BN(1000), proof)
+                        .accountsStrict({
+                            claim: claimV2Accounts(cohortEpochPda, cohortClaimPda, cohortIndex),
+                            cohort: cohortPdaFor(cohortIndex),
+                        })
+                        .signers([user])
+                        .rpc();
+                    assert.fail("Should have thrown InvalidMerkleProof");
+                } catch (err) {
+                    expect(err.toString()).to.include("InvalidMerkleProof");
+                }
+            });
+
+            it("claims a valid cohort allocation once", async () => {
+                const amount = new anchor.BN(700);
+                const proof = proofFor(cohortMerkle, user.publicKey, amount, cohortIndex);
+                const balanceBefore = (await getAccount(provider.connection, userMintTokenAccount)).amount;
+                await program.methods
+                    .claimCohortRewards(amount, proof)
+                    .accountsStrict({
+                        claim: claimV2Accounts(cohortEpochPda, cohortClaimPda, cohortIndex),
+                        cohort: cohortPdaFor(cohortIndex),
+                    })
+                    .signers([user])
+                    .rpc();
+                const balanceAfter = (await getAccount(provider.connection, userMintTokenAccount)).amount;
+                assert.equal(balanceAfter, balanceBefore + BigInt(700));
+
+                try {
+                    await program.methods
+                        .claimCohortRewards(amount, proof)
+                        .accountsStrict({
+                            claim: claimV2Accounts(cohortEpochPda, cohortClaimPda, cohortIndex),
+                            cohort: cohortPdaFor(cohortIndex),
+                        })
+                        .signers([user])
+                        .rpc();
+                    assert.fail("Should have rejected the double claim");
+                } catch (err) {
+                    expect(err).to.exist;
+                }
+            });
+
+            it("rejects claim_cohort_rewards against an epoch that is not a cohort", async () => {
+                // epoch 53 is a plain V2 epoch: no CohortRewards PDA exists for it
+                const plainIndex = 53;
+                const { epoch: plainEpochPda } = deriveRewardsEpochV2Accounts(program.programId, plainIndex);
+                const [plainClaimPda] = PublicKey.findProgramAddressSync(
+                    [Buffer.from("claim"), plainEpochPda.toBuffer(), user.publicKey.toBuffer()],
+                    program.programId
+                );
+                try {
+                    await program.methods
+                        .claimCohortRewards(new anchor.BN(1), [])
+                        .accountsStrict({
+                            claim: claimV2Accounts(plainEpochPda, plainClaimPda, plainIndex),
+                            cohort: cohortPdaFor(plainIndex),
+                        })
+                        .signers([user])
+                        .rpc();
+                    assert.fail("Should have thrown AccountNotInitialized");
+                } catch (err) {
+                    expect(err.toString()).to.include("AccountNotInitialized");
+                }
+            });
+        });
     }); // end describe("rewards v2")
 
     describe("updateability", () => {